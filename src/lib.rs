@@ -35,7 +35,12 @@ pub const MAX_LIQUIDATION_FEE_BPS: u64 = 10_000;
 pub const MAX_PROTOCOL_FEE_ABS: u128 = 1_000_000_000_000_000_000_000_000_000_000_000_000;
 pub const MAX_WARMUP_SLOTS: u64 = u64::MAX;
 pub const MAX_RESOLVE_PRICE_DEVIATION_BPS: u64 = 10_000;
+pub const DEFAULT_RESOLVE_PRICE_DEVIATION_BPS: u64 = 500;
+pub const DEFAULT_FUNDING_MARK_BAND_BPS: u64 = 100;
 pub const MAX_RECOVERY_FALLBACK_DEVIATION_BPS: u64 = MAX_RESOLVE_PRICE_DEVIATION_BPS;
+pub const DEFAULT_CONFIG_UPDATE_TIMELOCK_SLOTS: u64 = 216_000;
+pub const DEFAULT_CONFIG_UPDATE_GRACE_SLOTS: u64 = 216_000;
+pub const MAX_CONFIG_UPDATE_GRACE_SLOTS: u64 = 10 * DEFAULT_CONFIG_UPDATE_GRACE_SLOTS;
 
 #[cfg(kani)]
 pub mod v16;
@@ -54,25 +59,34 @@ pub use v16::*;
 pub use v16::{
     active_bitmap_count_ones, active_bitmap_empty, active_bitmap_get, active_bitmap_is_empty,
     backing_domain_fee_split_for_lien_delta_num, v16_domain_count_for_market_slots,
-    v16_domain_pair_for_asset_index, AccrueAssetOutcomeV16, AssetLifecycleV16, AssetStateV16,
+    v16_domain_pair_for_asset_index, AccrueAssetOutcomeV16, AssetLifecycleV16,
+    AssetRiskProfileUpdateV16, AssetRiskProfileV16, AssetRiskProfileV16Account, AssetStateV16,
     AssetStateV16Account, BackingBucketStatusV16, BackingBucketV16, BackingBucketV16Account,
     BackingDomainFeeSplitV16, BatchTradeOutcomeV16, CloseProgressLedgerV16,
-    CloseProgressLedgerV16Account, DeadLegForfeitOutcomeV16, EngineAssetSlotV16Account,
+    CloseProgressLedgerV16Account, ConfigUpdateV16, DeadLegForfeitOutcomeV16,
+    EngineAssetSlotV16Account, EngineEventV16, EventSinkV16, FeeTierV16, FeeTierV16Account,
     HealthCertV16, HealthCertV16Account, InsuranceCreditReservationV16,
-    InsuranceCreditReservationV16Account, LiquidationOutcomeV16, LiquidationRequestV16, Market,
-    MarketGroupV16HeaderAccount, MarketGroupV16View, MarketGroupV16ViewMut, MarketModeV16,
-    MarketSlotV16View, MarketSlotV16ViewMut, PermissionlessCrankActionV16,
-    PermissionlessCrankRequestV16, PermissionlessProgressOutcomeV16,
+    InsuranceCreditReservationV16Account, IsolatedMarginV16, IsolatedMarginV16Account,
+    KeeperCandidateV16, KeeperCrankOutcomeV16, KeeperCrankRequestV16, LienActionV16, LienSourceV16,
+    LiquidationOutcomeV16, LiquidationRequestV16, MarginBracketV16, MarginBracketV16Account,
+    Market, MarketGroupV16HeaderAccount, MarketGroupV16Transaction, MarketGroupV16View,
+    MarketGroupV16ViewMut, MarketModeV16, MarketSlotV16View, MarketSlotV16ViewMut,
+    OracleCatchupOutcomeV16, PendingConfigUpdateV16, PendingConfigUpdateV16Account,
+    PermissionlessCrankActionV16, PermissionlessCrankRequestV16, PermissionlessProgressOutcomeV16,
     PermissionlessRecoveryReasonV16, PortfolioAccountV16Account, PortfolioLegV16,
-    PortfolioLegV16Account, PortfolioSourceDomainV16Account, PortfolioV16View, PortfolioV16ViewMut,
-    ProvenanceHeaderV16, ProvenanceHeaderV16Account, RebalanceOutcomeV16, RebalanceRequestV16,
-    ResolvedCloseOutcomeV16, ResolvedPayoutLedgerV16, ResolvedPayoutLedgerV16Account,
-    ResolvedPayoutReceiptV16, ResolvedPayoutReceiptV16Account, SideModeV16, SideV16,
-    SourceCreditStateV16, SourceCreditStateV16Account, TradeRequestV16, V16ActiveBitmap, V16Config,
-    V16ConfigAccount, V16Error, V16OptionalRecoveryReasonAccount, V16PodI128, V16PodU128,
-    V16PodU16, V16PodU32, V16PodU64, V16Result, PORTFOLIO_SOURCE_DOMAIN_CAP,
-    V16_ACCOUNT_VERSION, V16_EMPTY_ACTIVE_BITMAP, V16_LAYOUT_DISCRIMINATOR,
-    V16_MAX_PORTFOLIO_ASSETS_N,
+    PortfolioLegV16Account, PortfolioMarginOffsetV16, PortfolioMarginOffsetV16Account,
+    PortfolioSourceDomainV16Account, PortfolioV16View, PortfolioV16ViewMut, ProvenanceHeaderV16,
+    ProvenanceHeaderV16Account, RebalanceOutcomeV16, RebalanceRequestV16, ResolveAssetPriceV16,
+    ResolveModeV16, ResolvedCloseOutcomeV16, ResolvedPayoutLedgerV16,
+    ResolvedPayoutLedgerV16Account, ResolvedPayoutReceiptV16, ResolvedPayoutReceiptV16Account,
+    SideModeV16, SideV16, SimulatedBatchTradeV16, SimulatedLiquidationV16, SimulatedPortfolioV16,
+    SimulatedWithdrawV16, SourceCreditStateV16, SourceCreditStateV16Account, TradeMakerV16,
+    TradeRequestV16, V16ActiveBitmap, V16Config, V16ConfigAccount, V16Error, V16ErrorReason,
+    V16OptionalRecoveryReasonAccount, V16PodI128, V16PodU128, V16PodU16, V16PodU32, V16PodU64,
+    V16Rejection, V16Result, WarmupAdmissionV16, WarmupReserveV16, WarmupReserveV16Account,
+    PORTFOLIO_SOURCE_DOMAIN_CAP, V16_ACCOUNT_VERSION, V16_EMPTY_ACTIVE_BITMAP,
+    V16_LAYOUT_DISCRIMINATOR, V16_MAX_FEE_TIERS, V16_MAX_MARGIN_BRACKETS,
+    V16_MAX_PORTFOLIO_ASSETS_N, V16_MAX_PORTFOLIO_MARGIN_OFFSETS,
 };
 
 // kani_active_bitmap_set is gated #[cfg(any(kani, test, feature="fork-facade"))]
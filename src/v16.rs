@@ -11,11 +11,16 @@ use crate::wide_math::{
     wide_mul_div_floor_u128, wide_signed_mul_div_floor_from_k_pair, U256,
 };
 use crate::{
-    ADL_ONE, BOUND_SCALE, CREDIT_RATE_SCALE, FUNDING_DEN, MAX_ACCOUNT_NOTIONAL, MAX_MARGIN_BPS,
-    MAX_ORACLE_PRICE, MAX_POSITION_ABS_Q, MAX_PROTOCOL_FEE_ABS,
-    MAX_RECOVERY_FALLBACK_DEVIATION_BPS, MAX_TRADE_SIZE_Q, MAX_VAULT_TVL, MIN_A_SIDE, POS_SCALE,
-    SOCIAL_LOSS_DEN, SOCIAL_WEIGHT_SCALE,
+    ADL_ONE, BOUND_SCALE, CREDIT_RATE_SCALE, DEFAULT_CONFIG_UPDATE_GRACE_SLOTS,
+    DEFAULT_CONFIG_UPDATE_TIMELOCK_SLOTS, DEFAULT_FUNDING_MARK_BAND_BPS,
+    DEFAULT_RESOLVE_PRICE_DEVIATION_BPS, FUNDING_DEN, MAX_ACCOUNT_NOTIONAL,
+    MAX_CONFIG_UPDATE_GRACE_SLOTS, MAX_MARGIN_BPS, MAX_ORACLE_PRICE, MAX_POSITION_ABS_Q,
+    MAX_PROTOCOL_FEE_ABS, MAX_RECOVERY_FALLBACK_DEVIATION_BPS, MAX_RESOLVE_PRICE_DEVIATION_BPS,
+    MAX_TRADE_SIZE_Q, MAX_VAULT_TVL, MIN_A_SIDE, POS_SCALE, SOCIAL_LOSS_DEN, SOCIAL_WEIGHT_SCALE,
+    STRESS_CONSUMPTION_SCALE,
 };
+use alloc::vec::Vec;
+use core::cell::Cell;
 
 pub const V16_MAX_PORTFOLIO_ASSETS_N: usize = 16;
 #[cfg(kani)]
@@ -27,10 +32,16 @@ pub type V16ActiveBitmap = [u64; V16_ACTIVE_BITMAP_WORDS];
 pub const V16_EMPTY_ACTIVE_BITMAP: V16ActiveBitmap = [0; V16_ACTIVE_BITMAP_WORDS];
 pub const V16_BACKING_BUCKETS_PER_DOMAIN: usize = 1;
 pub const V16_LAYOUT_DISCRIMINATOR: u16 = 16;
-pub const V16_ACCOUNT_VERSION: u16 = 1;
+/// Account layout version stamped into every provenance header; bumped whenever a Pod layout
+/// grows. Accounts stamped with an older version fail `validate_with_market` with
+/// `ProvenanceMismatch` and must be re-created.
+pub const V16_ACCOUNT_VERSION: u16 = 2;
 pub const BACKING_FEE_RATE_DEN_E9: u128 = 1_000_000_000;
 pub const MAX_BACKING_FEE_RATE_E9_PER_SLOT: u64 = 1_000_000_000;
 pub const MAX_BACKING_FEE_UTIL_BPS: u64 = 10_000;
+pub const V16_MAX_PORTFOLIO_MARGIN_OFFSETS: usize = 8;
+pub const V16_MAX_MARGIN_BRACKETS: usize = 4;
+pub const V16_MAX_FEE_TIERS: usize = 4;
 
 /// fork feature A-6 stress envelope: trigger threshold (bps x 1e9) for the
 /// `stress_consumption_bps_e9_since_envelope` accumulator. When the accumulator crosses this value,
@@ -213,6 +224,133 @@ pub enum V16Error {
     CounterUnderflow,
 }
 
+impl V16Error {
+    pub const fn with_reason(self, reason: V16ErrorReason) -> V16Rejection {
+        V16Rejection {
+            error: self,
+            reason,
+        }
+    }
+}
+
+/// Stable reason sub-code recorded at a rejection site, alongside the `V16Error` it returns.
+/// Values are part of the public error surface: they are never renumbered, and new reasons take
+/// fresh values inside their operation's hundred-block. `Unspecified` marks internal invariant
+/// and arithmetic checks that carry no user-actionable cause.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum V16ErrorReason {
+    Unspecified = 0,
+    MarketNotLive = 1,
+    MarketNotResolved = 2,
+    MarketInRecovery = 3,
+    MarketNotInRecovery = 4,
+    AssetIndexOutOfRange = 5,
+    SlotBehindMarket = 6,
+    AccountLegCapacityExhausted = 7,
+    MarketResolved = 8,
+
+    WithdrawWithOpenPositions = 100,
+    WithdrawCloseInProgress = 101,
+    WithdrawUnsettledLoss = 102,
+    WithdrawExceedsCapital = 103,
+    WithdrawNegativeEquity = 104,
+
+    TradeRequestOutOfBounds = 200,
+    InitialMarginCertStale = 201,
+    InitialMarginInsufficient = 202,
+    InitialMarginRequiresPositiveCredit = 203,
+    TradeTouchesPendingLossBarrier = 204,
+    TradeRiskIncreaseWhileLossStale = 205,
+    TradeRiskIncreaseWhileTargetLagged = 206,
+    TradeReduceOnlyViolated = 207,
+    TradePostOnlyWouldTake = 208,
+    TradeMakerRebateExceedsTakerFee = 209,
+
+    LiquidationRequestOutOfBounds = 300,
+    LiquidationAccountHealthy = 301,
+    LiquidationBChunkPending = 302,
+    LiquidationTouchesPendingLossBarrier = 303,
+    LiquidationRequiresRecovery = 304,
+    LiquidationIsolatedMarginHealthy = 305,
+    LiquidationCrossMarginHealthy = 306,
+    LiquidationTransferBankrupt = 307,
+    LiquidationLegEmpty = 308,
+
+    ResolveSlotInPast = 400,
+    ResolvedCloseRequiresRecovery = 401,
+    ResolvePriceOutOfBand = 402,
+    ResolveLivePriceNotSynced = 403,
+    ResolveDegenerateRequiresLastPrice = 404,
+    AssetNotResolved = 405,
+    AssetResolved = 406,
+    RecoveryFallbackDisabled = 407,
+    RecoveryFallbackPriceOutOfBand = 408,
+    ResolvePriceCountMismatch = 409,
+    ResolvePriceOutOfRange = 410,
+    AssetNotResolvable = 411,
+    ResolvedCloseZeroBudget = 412,
+    PermissionlessRecoveryDisabled = 413,
+
+    OracleCatchupStalled = 500,
+    AccrualRequestOutOfBounds = 501,
+    AccrualFundingRateOutOfBounds = 502,
+    AccrualFundingRateNotEngineDerived = 503,
+
+    ConfigUpdateAlreadyPending = 600,
+    ConfigUpdateNotPending = 601,
+    ConfigUpdateTimelocked = 602,
+    ConfigUpdateAssetNotActive = 603,
+    ConfigUpdateInvalidConfig = 604,
+    ConfigUpdateInvalidRiskProfile = 605,
+
+    PortfolioMarginOffsetUnsafe = 700,
+    PortfolioMarginOffsetDuplicatePair = 701,
+
+    IsolatedMarginBelowInitial = 800,
+    IsolatedMarginExceedsCapital = 801,
+    AccountInitialMarginOutOfBounds = 802,
+
+    WarmupAdmissionOutOfBounds = 900,
+    WarmupAdmissionScopeActive = 901,
+
+    KeeperSweepWindowMismatch = 1000,
+    KeeperSweepAccountOutOfOrder = 1001,
+    KeeperAccountRepeated = 1002,
+    MaterializedPortfolioNotEmpty = 1003,
+    MaterializedPortfolioAlreadyIndexed = 1004,
+    MaterializedPortfolioNotIndexed = 1005,
+    MaterializedLastPortfolioMismatch = 1006,
+}
+
+/// A `V16Error` together with the reason recorded at its rejection site. Engine calls return the
+/// bare `V16Error`; `MarketGroupV16ViewMut::call_with_reason` runs a call and reports the pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct V16Rejection {
+    pub error: V16Error,
+    pub reason: V16ErrorReason,
+}
+
+impl V16Rejection {
+    /// Error in the high 16 bits, reason in the low 16 bits; suitable as a custom program error
+    /// number.
+    pub const fn code(self) -> u32 {
+        ((self.error as u32) << 16) | self.reason as u32
+    }
+}
+
+impl From<V16Error> for V16Rejection {
+    fn from(error: V16Error) -> Self {
+        error.with_reason(V16ErrorReason::Unspecified)
+    }
+}
+
+impl From<V16Rejection> for V16Error {
+    fn from(rejection: V16Rejection) -> Self {
+        rejection.error
+    }
+}
+
 pub type V16Result<T> = core::result::Result<T, V16Error>;
 
 #[inline]
@@ -530,6 +668,105 @@ impl V16Core {
         }
     }
 
+    /// Engine funding rate from the asset's trailing TWAPs: `premium + clamp(interest - premium,
+    /// +-clamp)`, bounded by `max_abs_funding_e9_per_slot`. The premium is the mark/index TWAP
+    /// gap as a fraction of the index, spread evenly over the window. Positive means longs pay.
+    fn premium_index_funding_rate_e9(config: V16Config, asset: AssetStateV16) -> V16Result<i128> {
+        let window = config.funding_twap_window_slots;
+        if window == 0 {
+            return Err(V16Error::InvalidConfig);
+        }
+        let premium = if asset.funding_index_cum == 0 {
+            0
+        } else {
+            let mark_cum =
+                i128::try_from(asset.funding_mark_cum).map_err(|_| V16Error::ArithmeticOverflow)?;
+            let index_cum = i128::try_from(asset.funding_index_cum)
+                .map_err(|_| V16Error::ArithmeticOverflow)?;
+            (mark_cum - index_cum)
+                .checked_mul(FUNDING_DEN as i128)
+                .ok_or(V16Error::ArithmeticOverflow)?
+                / index_cum
+                / window as i128
+        };
+        let clamp = config.funding_premium_clamp_e9_per_slot as i128;
+        let interest = config.funding_interest_rate_e9_per_slot as i128;
+        let max_abs = config.max_abs_funding_e9_per_slot as i128;
+        let rate = premium + (interest - premium).clamp(-clamp, clamp);
+        Ok(rate.clamp(-max_abs, max_abs))
+    }
+
+    /// Per-side funding numerator for one segment, floored at `FUNDING_DEN` before the side's
+    /// A factor is applied.
+    fn funding_num_for_segment(
+        rate_e9: i128,
+        segment_dt: u64,
+        effective_price: u64,
+    ) -> V16Result<i128> {
+        let n = rate_e9
+            .checked_mul(segment_dt as i128)
+            .and_then(|v| v.checked_mul(effective_price as i128))
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        Ok(floor_div_signed_conservative_i128(n, FUNDING_DEN))
+    }
+
+    /// Rolls the accrual segment starting at `asset.slot_last` into the trailing TWAP sums,
+    /// first shrinking the retained history pro rata so the sums never cover more than the
+    /// window. The mark is the last fill clamped to `funding_mark_band_bps` around the index,
+    /// or the index itself when no fill is on record or the last one is a full window old.
+    fn roll_funding_twap(
+        asset: &mut AssetStateV16,
+        config: V16Config,
+        segment_dt: u64,
+        index_price: u64,
+    ) -> V16Result<()> {
+        let window = config.funding_twap_window_slots;
+        if window == 0 || segment_dt == 0 {
+            return Ok(());
+        }
+        let dt = segment_dt.min(window);
+        let keep = window - dt;
+        if asset.funding_window_slots > keep {
+            asset.funding_mark_cum = wide_mul_div_floor_u128(
+                asset.funding_mark_cum,
+                keep as u128,
+                asset.funding_window_slots as u128,
+            );
+            asset.funding_index_cum = wide_mul_div_floor_u128(
+                asset.funding_index_cum,
+                keep as u128,
+                asset.funding_window_slots as u128,
+            );
+            asset.funding_window_slots = keep;
+        }
+        let mark = if asset.funding_mark_price == 0
+            || asset.slot_last.saturating_sub(asset.funding_mark_slot) >= window
+        {
+            index_price as u128
+        } else {
+            let band = wide_mul_div_floor_u128(
+                index_price as u128,
+                config.funding_mark_band_bps as u128,
+                MAX_MARGIN_BPS as u128,
+            );
+            (asset.funding_mark_price as u128)
+                .clamp(index_price as u128 - band, index_price as u128 + band)
+        };
+        asset.funding_mark_cum = asset
+            .funding_mark_cum
+            .checked_add(
+                mark.checked_mul(dt as u128)
+                    .ok_or(V16Error::ArithmeticOverflow)?,
+            )
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        asset.funding_index_cum = asset
+            .funding_index_cum
+            .checked_add((index_price as u128) * (dt as u128))
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        asset.funding_window_slots += dt;
+        Ok(())
+    }
+
     #[inline]
     fn liquidation_progress_from_scores(before: RiskScoreV16, after: RiskScoreV16) -> bool {
         after.strictly_reduces_from(before)
@@ -1186,16 +1423,8 @@ impl V16Core {
         risk_notional: u128,
         target_lag_penalty: u128,
     ) -> V16Result<(u128, u128, u128)> {
-        let base_initial = margin_requirement(
-            risk_notional,
-            config.initial_margin_bps,
-            config.min_nonzero_im_req,
-        )?;
-        let base_maintenance = margin_requirement(
-            risk_notional,
-            config.maintenance_margin_bps,
-            config.min_nonzero_mm_req,
-        )?;
+        let base_initial = config.initial_requirement(risk_notional)?;
+        let base_maintenance = config.maintenance_requirement(risk_notional)?;
         Self::health_requirements_from_base_and_target_lag(
             base_initial,
             base_maintenance,
@@ -1485,6 +1714,54 @@ impl ProvenanceHeaderV16 {
     }
 }
 
+/// Notional bracket for tiered margin. Leg notional at or above `notional_threshold` is charged
+/// at the bracket's rates (never below the base rates); notional below the first threshold stays
+/// at the base rates. Unused brackets are all-zero and follow the used ones.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarginBracketV16 {
+    pub notional_threshold: u128,
+    pub maintenance_margin_bps: u64,
+    pub initial_margin_bps: u64,
+}
+
+impl MarginBracketV16 {
+    pub const EMPTY: Self = Self {
+        notional_threshold: 0,
+        maintenance_margin_bps: 0,
+        initial_margin_bps: 0,
+    };
+}
+
+impl Default for MarginBracketV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// Volume tier for trading fees. An account whose decayed traded notional is at or above
+/// `volume_threshold` pays its fees less `fee_discount_bps` of them. Unused tiers are all-zero
+/// and follow the used ones.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeTierV16 {
+    pub volume_threshold: u128,
+    pub fee_discount_bps: u64,
+}
+
+impl FeeTierV16 {
+    pub const EMPTY: Self = Self {
+        volume_threshold: 0,
+        fee_discount_bps: 0,
+    };
+}
+
+impl Default for FeeTierV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct V16Config {
@@ -1494,14 +1771,38 @@ pub struct V16Config {
     pub min_nonzero_im_req: u128,
     pub h_min: u64,
     pub h_max: u64,
+    /// Warmup admission pair applied when a trade, crank or convert is called without one.
+    /// Must lie within `[h_min, h_max]` with a nonzero `default_admit_h_max`.
+    pub default_admit_h_min: u64,
+    pub default_admit_h_max: u64,
     pub maintenance_margin_bps: u64,
     pub initial_margin_bps: u64,
     pub max_trading_fee_bps: u64,
     pub liquidation_fee_bps: u64,
     pub liquidation_fee_cap: u128,
     pub min_liquidation_abs: u128,
+    /// Share of the closed notional a backstop liquidator is paid for taking a leg over,
+    /// floored and capped like the liquidation fee and bounded by `liquidation_fee_bps`.
+    pub backstop_liquidation_discount_bps: u64,
+    /// Dutch-auction liquidation: share of the full fee (and backstop discount) charged on the
+    /// slot an account is first seen liquidatable, growing linearly to the full rate over
+    /// `liquidation_auction_ramp_slots`. A zero ramp charges the full rate at once.
+    pub liquidation_auction_start_bps: u64,
+    pub liquidation_auction_ramp_slots: u64,
+    /// Engine funding model: trailing mark/index TWAP window. Zero keeps the caller-supplied
+    /// accrual rate; nonzero derives the rate from the premium, the interest rate and the clamp,
+    /// and accruals must then pass a zero rate.
+    pub funding_twap_window_slots: u64,
+    pub funding_interest_rate_e9_per_slot: u64,
+    pub funding_premium_clamp_e9_per_slot: u64,
+    /// Band around the effective price, in bps, that a fill price is clamped to before it is
+    /// sampled as the funding mark.
+    pub funding_mark_band_bps: u64,
     pub max_accrual_dt_slots: u64,
     pub max_abs_funding_e9_per_slot: u64,
+    /// Extra rate charged to the paying side of a nonzero funding rate. The receiving side is
+    /// credited the base rate only; the difference accrues to the asset for insurance.
+    pub funding_spread_e9_per_slot: u64,
     pub min_funding_lifetime_slots: u64,
     pub max_price_move_bps_per_slot: u64,
     pub max_account_b_settlement_chunks: u64,
@@ -1510,6 +1811,14 @@ pub struct V16Config {
     pub asset_activation_cooldown_slots: u64,
     pub public_b_chunk_atoms: u128,
     pub max_recovery_fallback_deviation_bps: u64,
+    pub resolve_price_deviation_bps: u64,
+    pub config_update_timelock_slots: u64,
+    pub config_update_grace_slots: u64,
+    pub margin_brackets: [MarginBracketV16; V16_MAX_MARGIN_BRACKETS],
+    pub fee_tiers: [FeeTierV16; V16_MAX_FEE_TIERS],
+    /// Slots over which an account's traded notional decays linearly to zero; required nonzero
+    /// once a fee tier is configured.
+    pub fee_volume_window_slots: u64,
     pub backing_fee_base_rate_e9_per_slot: u64,
     pub backing_fee_kink_util_bps: u64,
     pub backing_fee_slope_at_kink_e9_per_slot: u64,
@@ -1550,14 +1859,24 @@ impl V16Config {
             min_nonzero_im_req: 2,
             h_min,
             h_max,
+            default_admit_h_min: h_min,
+            default_admit_h_max: h_max,
             maintenance_margin_bps: 10_000,
             initial_margin_bps: 10_000,
             max_trading_fee_bps: 0,
             liquidation_fee_bps: 0,
             liquidation_fee_cap: 0,
             min_liquidation_abs: 0,
+            backstop_liquidation_discount_bps: 0,
+            liquidation_auction_start_bps: 0,
+            liquidation_auction_ramp_slots: 0,
+            funding_twap_window_slots: 0,
+            funding_interest_rate_e9_per_slot: 0,
+            funding_premium_clamp_e9_per_slot: 0,
+            funding_mark_band_bps: DEFAULT_FUNDING_MARK_BAND_BPS,
             max_accrual_dt_slots: 1,
             max_abs_funding_e9_per_slot: 0,
+            funding_spread_e9_per_slot: 0,
             min_funding_lifetime_slots: 1,
             max_price_move_bps_per_slot: 10_000,
             max_account_b_settlement_chunks: 1,
@@ -1566,6 +1885,12 @@ impl V16Config {
             asset_activation_cooldown_slots: 1,
             public_b_chunk_atoms: MAX_VAULT_TVL,
             max_recovery_fallback_deviation_bps: MAX_RECOVERY_FALLBACK_DEVIATION_BPS,
+            resolve_price_deviation_bps: DEFAULT_RESOLVE_PRICE_DEVIATION_BPS,
+            config_update_timelock_slots: DEFAULT_CONFIG_UPDATE_TIMELOCK_SLOTS,
+            config_update_grace_slots: DEFAULT_CONFIG_UPDATE_GRACE_SLOTS,
+            margin_brackets: [MarginBracketV16::EMPTY; V16_MAX_MARGIN_BRACKETS],
+            fee_tiers: [FeeTierV16::EMPTY; V16_MAX_FEE_TIERS],
+            fee_volume_window_slots: 0,
             backing_fee_base_rate_e9_per_slot: 0,
             backing_fee_kink_util_bps: 8_000,
             backing_fee_slope_at_kink_e9_per_slot: 0,
@@ -1638,6 +1963,8 @@ impl V16Config {
             .ok_or(V16Error::InvalidConfig)?;
         let worst_liq_notional =
             Self::checked_mul_div_ceil_to_u128(n, worst_liq_multiplier, 10_000)?;
+        // The liquidation auction only ramps up to `liquidation_fee_bps`, so its end rate is
+        // the worst case priced here.
         let liq_fee_raw = Self::checked_mul_div_ceil_to_u128(
             worst_liq_notional,
             self.liquidation_fee_bps as u128,
@@ -1652,6 +1979,11 @@ impl V16Config {
     }
 
     fn maintenance_requirement_for_notional(&self, n: u128) -> V16Result<u128> {
+        if self.margin_brackets[0].notional_threshold != 0 {
+            return self
+                .maintenance_requirement(n)
+                .map_err(|_| V16Error::InvalidConfig);
+        }
         let mm_prop = if let Some(product) = n.checked_mul(self.maintenance_margin_bps as u128) {
             product / 10_000
         } else {
@@ -1770,11 +2102,22 @@ impl V16Config {
         Ok(())
     }
 
+    /// Fastest per-slot funding rate any side can be charged: the rate cap plus the spread the
+    /// paying side carries on top of it.
+    fn max_paying_funding_e9_per_slot(&self) -> u64 {
+        self.max_abs_funding_e9_per_slot
+            .saturating_add(self.funding_spread_e9_per_slot)
+    }
+
     fn validate_funding_headroom(&self, slots: u64) -> V16Result<()> {
         let max_signed = U256::from_u128(i128::MAX as u128);
         let headroom = U256::from_u128(ADL_ONE)
             .checked_mul(U256::from_u128(MAX_ORACLE_PRICE as u128))
-            .and_then(|v| v.checked_mul(U256::from_u128(self.max_abs_funding_e9_per_slot as u128)))
+            .and_then(|v| {
+                v.checked_mul(U256::from_u128(
+                    self.max_paying_funding_e9_per_slot() as u128
+                ))
+            })
             .and_then(|v| v.checked_mul(U256::from_u128(slots as u128)))
             .ok_or(V16Error::InvalidConfig)?;
         if headroom <= max_signed {
@@ -1790,7 +2133,7 @@ impl V16Config {
             .ok_or(V16Error::InvalidConfig)?;
         if self.maintenance_margin_bps == 10_000
             && price_budget_fast <= 10_000
-            && self.max_abs_funding_e9_per_slot == 0
+            && self.max_paying_funding_e9_per_slot() == 0
             && self.liquidation_fee_bps == 0
             && self.min_liquidation_abs == 0
         {
@@ -1802,7 +2145,7 @@ impl V16Config {
 
         let move_cap = U256::from_u128(self.max_price_move_bps_per_slot as u128);
         let dt = U256::from_u128(self.max_accrual_dt_slots as u128);
-        let rate = U256::from_u128(self.max_abs_funding_e9_per_slot as u128);
+        let rate = U256::from_u128(self.max_paying_funding_e9_per_slot() as u128);
         let ten_thousand = U256::from_u128(10_000);
         let funding_den = U256::from_u128(FUNDING_DEN);
 
@@ -1976,7 +2319,12 @@ impl V16Config {
         {
             return Err(V16Error::InvalidConfig);
         }
-        if self.h_max == 0 || self.h_min > self.h_max {
+        if self.h_max == 0
+            || self.h_min > self.h_max
+            || !self
+                .default_warmup_admission()
+                .within(self.h_min, self.h_max)
+        {
             return Err(V16Error::InvalidConfig);
         }
         if self.min_nonzero_mm_req == 0 || self.min_nonzero_mm_req >= self.min_nonzero_im_req {
@@ -1986,11 +2334,18 @@ impl V16Config {
             || self.initial_margin_bps > MAX_MARGIN_BPS
             || self.max_trading_fee_bps > MAX_MARGIN_BPS
             || self.liquidation_fee_bps > MAX_MARGIN_BPS
+            || self.backstop_liquidation_discount_bps > self.liquidation_fee_bps
+            || self.liquidation_auction_start_bps > MAX_MARGIN_BPS
             || self.min_liquidation_abs > self.liquidation_fee_cap
             || self.liquidation_fee_cap > MAX_PROTOCOL_FEE_ABS
             || self.max_accrual_dt_slots == 0
             || self.min_funding_lifetime_slots < self.max_accrual_dt_slots
-            || self.max_abs_funding_e9_per_slot > 10_000
+            || self.max_paying_funding_e9_per_slot() > 10_000
+            || (self.funding_twap_window_slots != 0
+                && (self.funding_interest_rate_e9_per_slot > self.max_abs_funding_e9_per_slot
+                    || self.funding_premium_clamp_e9_per_slot
+                        > self.max_abs_funding_e9_per_slot
+                    || self.funding_mark_band_bps > MAX_MARGIN_BPS))
             || self.max_price_move_bps_per_slot == 0
             // fork feature A-10: upper-bound the per-slot price-move cap (toly bounds only the
             // lower edge == 0). A config with max_price_move_bps_per_slot > MAX_MARGIN_BPS would
@@ -2002,6 +2357,9 @@ impl V16Config {
             || self.asset_activation_cooldown_slots == 0
             || self.public_b_chunk_atoms == 0
             || self.max_recovery_fallback_deviation_bps > MAX_RECOVERY_FALLBACK_DEVIATION_BPS
+            || self.resolve_price_deviation_bps > MAX_RESOLVE_PRICE_DEVIATION_BPS
+            || self.config_update_timelock_slots == 0
+            || self.config_update_grace_slots > MAX_CONFIG_UPDATE_GRACE_SLOTS
             || self.backing_fee_kink_util_bps == 0
             || self.backing_fee_kink_util_bps >= MAX_BACKING_FEE_UTIL_BPS
             || self.backing_freshness_buckets == 0
@@ -2009,6 +2367,8 @@ impl V16Config {
         {
             return Err(V16Error::InvalidConfig);
         }
+        self.validate_margin_brackets()?;
+        self.validate_fee_tiers()?;
         if self
             .backing_fee_base_rate_e9_per_slot
             .checked_add(self.backing_fee_slope_at_kink_e9_per_slot)
@@ -2034,11 +2394,280 @@ impl V16Config {
         Ok(())
     }
 
+    pub const fn default_warmup_admission(&self) -> WarmupAdmissionV16 {
+        WarmupAdmissionV16 {
+            admit_h_min: self.default_admit_h_min,
+            admit_h_max: self.default_admit_h_max,
+        }
+    }
+
     pub fn validate_public_user_fund(&self) -> V16Result<()> {
         self.validate_public_user_fund_shape()?;
         self.validate_exact_solvency_envelope()
     }
 
+    /// The group config as seen by one asset: an enabled `profile` replaces the per-asset risk
+    /// fields, a disabled one leaves the group values in place.
+    pub fn with_asset_risk_profile(mut self, profile: AssetRiskProfileV16) -> Self {
+        if profile.enabled {
+            self.maintenance_margin_bps = profile.maintenance_margin_bps;
+            self.initial_margin_bps = profile.initial_margin_bps;
+            self.liquidation_fee_bps = profile.liquidation_fee_bps;
+            self.max_price_move_bps_per_slot = profile.max_price_move_bps_per_slot;
+            self.max_abs_funding_e9_per_slot = profile.max_abs_funding_e9_per_slot;
+        }
+        self
+    }
+
+    /// Validates `profile` by running the full public-fund validation, solvency envelope
+    /// included, on the config it would produce for its asset. A disabled profile must be
+    /// all-zero.
+    pub fn validate_asset_risk_profile(&self, profile: AssetRiskProfileV16) -> V16Result<()> {
+        if !profile.enabled {
+            if profile != AssetRiskProfileV16::EMPTY {
+                return Err(V16Error::InvalidConfig);
+            }
+            return Ok(());
+        }
+        self.with_asset_risk_profile(profile)
+            .validate_public_user_fund()
+    }
+
+    /// Largest cross-asset margin offset, in bps of a leg's base requirement, that still leaves
+    /// the leg's maintenance requirement covering the one-accrual linear loss budget (price move,
+    /// funding, worst-case liquidation fee) the solvency envelope charges. Zero when there is no
+    /// slack or when a liquidation floor applies.
+    pub fn max_portfolio_margin_offset_bps(&self) -> V16Result<u64> {
+        if self.min_liquidation_abs != 0 || self.maintenance_margin_bps == 0 {
+            return Ok(0);
+        }
+        let ten_thousand = U256::from_u128(10_000);
+        let price_budget_bps = (self.max_price_move_bps_per_slot as u128)
+            .checked_mul(self.max_accrual_dt_slots as u128)
+            .ok_or(V16Error::InvalidConfig)?;
+        let funding_budget_bps_ceil = Self::ceil_div_u256_to_u128(
+            U256::from_u128(self.max_paying_funding_e9_per_slot() as u128)
+                .checked_mul(U256::from_u128(self.max_accrual_dt_slots as u128))
+                .and_then(|v| v.checked_mul(ten_thousand))
+                .ok_or(V16Error::InvalidConfig)?,
+            U256::from_u128(FUNDING_DEN),
+        )?;
+        let worst_liq_budget_bps_ceil = Self::ceil_div_u256_to_u128(
+            U256::from_u128(
+                10_000u128
+                    .checked_add(price_budget_bps)
+                    .ok_or(V16Error::InvalidConfig)?,
+            )
+            .checked_mul(U256::from_u128(self.liquidation_fee_bps as u128))
+            .ok_or(V16Error::InvalidConfig)?,
+            ten_thousand,
+        )?;
+        let linear_budget_bps = price_budget_bps
+            .checked_add(funding_budget_bps_ceil)
+            .and_then(|v| v.checked_add(worst_liq_budget_bps_ceil))
+            .ok_or(V16Error::InvalidConfig)?;
+        let mm = self.maintenance_margin_bps as u128;
+        if linear_budget_bps >= mm {
+            return Ok(0);
+        }
+        Ok(((mm - linear_budget_bps) * 10_000 / mm) as u64)
+    }
+
+    /// Maintenance requirement for one leg's risk notional under the margin brackets.
+    pub fn maintenance_requirement(&self, notional: u128) -> V16Result<u128> {
+        self.bracketed_margin_requirement(
+            notional,
+            self.maintenance_margin_bps,
+            self.min_nonzero_mm_req,
+            false,
+        )
+    }
+
+    /// Initial requirement for one leg's risk notional under the margin brackets.
+    pub fn initial_requirement(&self, notional: u128) -> V16Result<u128> {
+        self.bracketed_margin_requirement(
+            notional,
+            self.initial_margin_bps,
+            self.min_nonzero_im_req,
+            true,
+        )
+    }
+
+    /// Charges each slice of `notional` at the rate of the bracket it falls in, summing the
+    /// slices before the single floor division so the result never drops below the flat
+    /// base-rate requirement. Brackets only ever raise the rate, which keeps the requirement
+    /// monotone and at least the linear one the solvency envelope certifies.
+    fn bracketed_margin_requirement(
+        &self,
+        notional: u128,
+        base_bps: u64,
+        floor: u128,
+        initial: bool,
+    ) -> V16Result<u128> {
+        if self.margin_brackets[0].notional_threshold == 0 {
+            return margin_requirement(notional, base_bps, floor);
+        }
+        if notional == 0 {
+            return Ok(0);
+        }
+        let mut num = U256::ZERO;
+        let mut lo = 0u128;
+        let mut bps = base_bps;
+        let mut i = 0usize;
+        while i < V16_MAX_MARGIN_BRACKETS && lo < notional {
+            let bracket = self.margin_brackets[i];
+            if bracket.notional_threshold == 0 {
+                break;
+            }
+            let hi = bracket.notional_threshold.min(notional);
+            if hi > lo {
+                num = U256::from_u128(hi - lo)
+                    .checked_mul(U256::from_u128(bps as u128))
+                    .and_then(|v| num.checked_add(v))
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+                lo = hi;
+            }
+            bps = if initial {
+                bracket.initial_margin_bps
+            } else {
+                bracket.maintenance_margin_bps
+            }
+            .max(base_bps);
+            i += 1;
+        }
+        num = U256::from_u128(notional - lo)
+            .checked_mul(U256::from_u128(bps as u128))
+            .and_then(|v| num.checked_add(v))
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        let raw = num
+            .checked_div(U256::from_u128(MAX_MARGIN_BPS as u128))
+            .and_then(|v| v.try_into_u128())
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        Ok(raw.max(floor))
+    }
+
+    /// Margin rate, in bps, charged on the notional unit at `notional` under the bracket schedule.
+    fn marginal_margin_bps(&self, notional: u128, initial: bool) -> u64 {
+        let base_bps = if initial {
+            self.initial_margin_bps
+        } else {
+            self.maintenance_margin_bps
+        };
+        let mut bps = base_bps;
+        let mut i = 0usize;
+        while i < V16_MAX_MARGIN_BRACKETS {
+            let bracket = self.margin_brackets[i];
+            if bracket.notional_threshold == 0 || bracket.notional_threshold > notional {
+                break;
+            }
+            bps = if initial {
+                bracket.initial_margin_bps
+            } else {
+                bracket.maintenance_margin_bps
+            }
+            .max(base_bps);
+            i += 1;
+        }
+        bps
+    }
+
+    /// True when moving from `previous` to `self` can turn a certified account unhealthy: a
+    /// higher maintenance or initial rate at any notional (base rates or brackets, so adding or
+    /// raising a bracket counts), a longer `h_max` warmup, or a lower per-slot price cap.
+    pub fn tightens_margin_from(&self, previous: &V16Config) -> bool {
+        if self.h_max > previous.h_max
+            || self.max_price_move_bps_per_slot < previous.max_price_move_bps_per_slot
+        {
+            return true;
+        }
+        // The rate schedules are step functions, so comparing them at zero and at every
+        // threshold of either schedule covers every notional.
+        let mut i = 0usize;
+        while i <= 2 * V16_MAX_MARGIN_BRACKETS {
+            let notional = if i == 0 {
+                0
+            } else if i <= V16_MAX_MARGIN_BRACKETS {
+                self.margin_brackets[i - 1].notional_threshold
+            } else {
+                previous.margin_brackets[i - 1 - V16_MAX_MARGIN_BRACKETS].notional_threshold
+            };
+            if self.marginal_margin_bps(notional, false)
+                > previous.marginal_margin_bps(notional, false)
+                || self.marginal_margin_bps(notional, true)
+                    > previous.marginal_margin_bps(notional, true)
+            {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    fn validate_margin_brackets(&self) -> V16Result<()> {
+        let mut prev = MarginBracketV16::EMPTY;
+        let mut i = 0usize;
+        while i < V16_MAX_MARGIN_BRACKETS {
+            let bracket = self.margin_brackets[i];
+            if bracket.notional_threshold == 0 {
+                if bracket != MarginBracketV16::EMPTY {
+                    return Err(V16Error::InvalidConfig);
+                }
+            } else if (i != 0 && prev.notional_threshold == 0)
+                || bracket.notional_threshold <= prev.notional_threshold
+                || bracket.maintenance_margin_bps < prev.maintenance_margin_bps
+                || bracket.initial_margin_bps < prev.initial_margin_bps
+                || bracket.maintenance_margin_bps > bracket.initial_margin_bps
+                || bracket.initial_margin_bps > MAX_MARGIN_BPS
+                || bracket.notional_threshold > MAX_ACCOUNT_NOTIONAL
+            {
+                return Err(V16Error::InvalidConfig);
+            }
+            prev = bracket;
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn validate_fee_tiers(&self) -> V16Result<()> {
+        if self.fee_tiers[0].volume_threshold != 0 && self.fee_volume_window_slots == 0 {
+            return Err(V16Error::InvalidConfig);
+        }
+        let mut prev = FeeTierV16::EMPTY;
+        let mut i = 0usize;
+        while i < V16_MAX_FEE_TIERS {
+            let tier = self.fee_tiers[i];
+            if tier.volume_threshold == 0 {
+                if tier != FeeTierV16::EMPTY {
+                    return Err(V16Error::InvalidConfig);
+                }
+            } else if (i != 0 && prev.volume_threshold == 0)
+                || tier.volume_threshold <= prev.volume_threshold
+                || tier.fee_discount_bps < prev.fee_discount_bps
+                || tier.fee_discount_bps > MAX_MARGIN_BPS
+            {
+                return Err(V16Error::InvalidConfig);
+            }
+            prev = tier;
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// Fee discount earned by `volume` of decayed traded notional: the highest tier it reaches.
+    pub fn fee_tier_discount_bps(&self, volume: u128) -> u64 {
+        let mut discount = 0;
+        let mut i = 0usize;
+        while i < V16_MAX_FEE_TIERS {
+            let tier = self.fee_tiers[i];
+            if tier.volume_threshold == 0 || volume < tier.volume_threshold {
+                break;
+            }
+            discount = tier.fee_discount_bps;
+            i += 1;
+        }
+        discount
+    }
+
     /// fork-facade (A-10): kani-only accessor for the SHAPE check in isolation, so the A-10
     /// `max_price_move_bps_per_slot > MAX_MARGIN_BPS` clause can be proven operative without the
     /// solvency-envelope path (which would mask the clause via an unrelated overflow rejection).
@@ -2055,7 +2684,7 @@ impl V16Config {
         let price_budget_bps = (self.max_price_move_bps_per_slot as u128)
             .checked_mul(self.max_accrual_dt_slots as u128)
             .ok_or(V16Error::InvalidConfig)?;
-        let funding_budget_num = (self.max_abs_funding_e9_per_slot as u128)
+        let funding_budget_num = (self.max_paying_funding_e9_per_slot() as u128)
             .checked_mul(self.max_accrual_dt_slots as u128)
             .and_then(|v| v.checked_mul(10_000))
             .ok_or(V16Error::InvalidConfig)?;
@@ -2119,6 +2748,19 @@ pub struct AssetStateV16 {
     pub epoch_short: u64,
     pub mode_long: SideModeV16,
     pub mode_short: SideModeV16,
+    /// Slot at which this asset alone was resolved inside a live group; zero while unresolved.
+    pub resolved_slot: u64,
+    pub resolved_price: u64,
+    /// Last fill price, sampled as the mark by the engine funding model; zero samples the index.
+    pub funding_mark_price: u64,
+    /// `slot_last` when `funding_mark_price` was recorded; a window-old sample reads the index.
+    pub funding_mark_slot: u64,
+    /// Time-weighted mark and index sums over the trailing `funding_window_slots`.
+    pub funding_mark_cum: u128,
+    pub funding_index_cum: u128,
+    pub funding_window_slots: u64,
+    /// Funding spread charged to the paying side and not yet swept into insurance.
+    pub funding_spread_unswept: u128,
 }
 
 impl Default for AssetStateV16 {
@@ -2165,10 +2807,92 @@ impl Default for AssetStateV16 {
             epoch_short: 0,
             mode_long: SideModeV16::Normal,
             mode_short: SideModeV16::Normal,
+            resolved_slot: 0,
+            resolved_price: 0,
+            funding_mark_price: 0,
+            funding_mark_slot: 0,
+            funding_mark_cum: 0,
+            funding_index_cum: 0,
+            funding_window_slots: 0,
+            funding_spread_unswept: 0,
         }
     }
 }
 
+/// Optional per-asset override of the group risk parameters, stored beside the asset's engine
+/// slot. While disabled the asset uses `V16Config` unchanged.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssetRiskProfileV16 {
+    pub enabled: bool,
+    pub maintenance_margin_bps: u64,
+    pub initial_margin_bps: u64,
+    pub liquidation_fee_bps: u64,
+    pub max_price_move_bps_per_slot: u64,
+    pub max_abs_funding_e9_per_slot: u64,
+}
+
+impl AssetRiskProfileV16 {
+    pub const EMPTY: Self = Self {
+        enabled: false,
+        maintenance_margin_bps: 0,
+        initial_margin_bps: 0,
+        liquidation_fee_bps: 0,
+        max_price_move_bps_per_slot: 0,
+        max_abs_funding_e9_per_slot: 0,
+    };
+}
+
+impl Default for AssetRiskProfileV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// Correlation offset between two assets for portfolio margin. A portfolio holding opposite
+/// sides of `asset_a` and `asset_b` has `offset_bps` of the smaller base requirement credited
+/// back on each leg. An all-zero entry is an unused table slot.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortfolioMarginOffsetV16 {
+    pub asset_a: u32,
+    pub asset_b: u32,
+    pub offset_bps: u64,
+}
+
+impl PortfolioMarginOffsetV16 {
+    pub const EMPTY: Self = Self {
+        asset_a: 0,
+        asset_b: 0,
+        offset_bps: 0,
+    };
+}
+
+impl Default for PortfolioMarginOffsetV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+#[derive(Clone, Copy)]
+struct PortfolioMarginLegV16 {
+    active: bool,
+    asset_index: usize,
+    side: SideV16,
+    base_initial: u128,
+    base_maintenance: u128,
+}
+
+impl PortfolioMarginLegV16 {
+    const EMPTY: Self = Self {
+        active: false,
+        asset_index: 0,
+        side: SideV16::Long,
+        base_initial: 0,
+        base_maintenance: 0,
+    };
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SourceCreditStateV16 {
@@ -2369,9 +3093,16 @@ pub struct MarketGroupV16View<'a, T> {
     pub markets: &'a [Market<T>],
 }
 
+/// Mutable engine view over a market group. Construct it with `new` (then `with_event_sink` if
+/// the host collects events): besides the public `header` and `markets` it holds private per-view
+/// state (event sink, warmup admission scope, recorded rejection reason), so the two-field struct
+/// literal that earlier versions accepted no longer compiles.
 pub struct MarketGroupV16ViewMut<'a, T> {
     pub header: &'a mut MarketGroupV16HeaderAccount,
     pub markets: &'a mut [Market<T>],
+    events: Option<&'a mut dyn EventSinkV16>,
+    admission: Option<WarmupAdmissionScopeV16>,
+    rejection: Cell<Option<V16Rejection>>,
 }
 
 impl<'a, T> MarketGroupV16View<'a, T> {
@@ -2382,7 +3113,48 @@ impl<'a, T> MarketGroupV16View<'a, T> {
 
 impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     pub fn new(header: &'a mut MarketGroupV16HeaderAccount, markets: &'a mut [Market<T>]) -> Self {
-        Self { header, markets }
+        Self {
+            header,
+            markets,
+            events: None,
+            admission: None,
+            rejection: Cell::new(None),
+        }
+    }
+
+    /// Routes engine events from every subsequent call on this view to `sink`. Events are
+    /// reported as the engine commits each step, so a host that rolls back a failed instruction
+    /// must discard the events it collected for it.
+    pub fn with_event_sink(mut self, sink: &'a mut dyn EventSinkV16) -> Self {
+        self.events = Some(sink);
+        self
+    }
+
+    /// Runs `op` on this view and reports a failure as the returned `V16Error` paired with the
+    /// reason its rejection site recorded (`Unspecified` when the site records none).
+    pub fn call_with_reason<R>(
+        &mut self,
+        op: impl FnOnce(&mut Self) -> V16Result<R>,
+    ) -> Result<R, V16Rejection> {
+        self.rejection.set(None);
+        op(self).map_err(|error| match self.rejection.take() {
+            Some(rejection) if rejection.error == error => rejection,
+            _ => V16Rejection::from(error),
+        })
+    }
+
+    /// Records `rejection` for `call_with_reason` and returns its error.
+    #[inline]
+    fn reject(&self, rejection: V16Rejection) -> V16Error {
+        self.rejection.set(Some(rejection));
+        rejection.error
+    }
+
+    #[inline]
+    fn emit(&mut self, event: EngineEventV16) {
+        if let Some(sink) = self.events.as_mut() {
+            sink.on_event(event);
+        }
     }
 
     pub fn as_view(&self) -> MarketGroupV16View<'_, T> {
@@ -2560,11 +3332,25 @@ impl<'a> PortfolioV16View<'a> {
         if self.header.reserved_pnl.get() > pnl.max(0) as u128 {
             return Err(V16Error::InvalidLeg);
         }
+        let warmup_reserve = self.header.warmup_reserve.try_to_runtime()?;
+        validate_warmup_reserve_value(warmup_reserve)?;
+        if warmup_reserve.total()? != self.header.reserved_pnl.get() {
+            return Err(V16Error::InvalidLeg);
+        }
         if self.header.residual_spent_principal_atoms_total.get()
             > self.header.residual_crystallized_loss_atoms_total.get()
         {
             return Err(V16Error::InvalidLeg);
         }
+        if decode_bool(self.header.materialized)? {
+            if self.header.materialized_index.get()
+                >= market.header.materialized_portfolio_count.get()
+            {
+                return Err(V16Error::ProvenanceMismatch);
+            }
+        } else if self.header.materialized_index.get() != 0 {
+            return Err(V16Error::InvalidLeg);
+        }
         self.validate_source_credit_shape_with_market(market)?;
         let source_claim_sum_num = self.source_claim_bound_sum_num()?;
         if source_claim_sum_num != 0 {
@@ -2584,6 +3370,9 @@ impl<'a> PortfolioV16View<'a> {
         while slot < V16_MAX_PORTFOLIO_ASSETS_N {
             let bit = active_bitmap_get(bitmap, slot);
             let leg = self.header.legs[slot].try_to_runtime()?;
+            if !leg.active && self.header.isolated_margin[slot].try_to_runtime()?.enabled {
+                return Err(V16Error::InvalidLeg);
+            }
             if slot >= active_leg_cap {
                 if bit || !leg.is_empty() {
                     return Err(V16Error::HiddenLeg);
@@ -2977,6 +3766,38 @@ impl Default for PortfolioLegV16 {
     }
 }
 
+/// Isolated-margin allocation for the leg in the same slot. `allocated_capital` is earmarked out
+/// of the account's capital; `settled_pnl` accumulates the leg's K/F settlements since the
+/// allocation was set, and only its losses draw the allocation down. While enabled the leg is
+/// margined and liquidated against its allocation alone.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsolatedMarginV16 {
+    pub enabled: bool,
+    pub allocated_capital: u128,
+    pub settled_pnl: i128,
+}
+
+impl IsolatedMarginV16 {
+    pub const EMPTY: Self = Self {
+        enabled: false,
+        allocated_capital: 0,
+        settled_pnl: 0,
+    };
+
+    /// Allocation left after the leg's settled losses, floored at zero.
+    pub fn equity(&self) -> u128 {
+        self.allocated_capital
+            .saturating_sub(self.settled_pnl.min(0).unsigned_abs())
+    }
+}
+
+impl Default for IsolatedMarginV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct HealthCertV16 {
@@ -3204,6 +4025,269 @@ pub fn kani_apply_resolved_payout_receipt_payment(
     apply_resolved_payout_receipt_payment(receipt, actual_resolved_paid)
 }
 
+/// Two-bucket warmup reserve backing `PortfolioAccountV16Account::reserved_pnl`.
+/// The scheduled bucket releases linearly over `sched_horizon` slots from
+/// `sched_start_slot`; the pending bucket waits until the scheduled bucket drains.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WarmupReserveV16 {
+    pub sched_start_slot: u64,
+    pub sched_horizon: u64,
+    pub sched_anchor: u128,
+    pub sched_remaining: u128,
+    pub sched_released: u128,
+    pub pending_remaining: u128,
+    pub pending_horizon: u64,
+}
+
+impl WarmupReserveV16 {
+    pub const EMPTY: Self = Self {
+        sched_start_slot: 0,
+        sched_horizon: 0,
+        sched_anchor: 0,
+        sched_remaining: 0,
+        sched_released: 0,
+        pending_remaining: 0,
+        pending_horizon: 0,
+    };
+
+    pub fn is_empty(self) -> bool {
+        self == Self::EMPTY
+    }
+
+    pub fn total(self) -> V16Result<u128> {
+        self.sched_remaining
+            .checked_add(self.pending_remaining)
+            .ok_or(V16Error::ArithmeticOverflow)
+    }
+
+    fn clear_scheduled(&mut self) {
+        self.sched_start_slot = 0;
+        self.sched_horizon = 0;
+        self.sched_anchor = 0;
+        self.sched_remaining = 0;
+        self.sched_released = 0;
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending_remaining = 0;
+        self.pending_horizon = 0;
+    }
+}
+
+impl Default for WarmupReserveV16 {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// Per-instruction warmup admission pair. Fresh positive PnL matures immediately when
+/// `admit_h_min` is admitted and it is zero; otherwise it is reserved for the admitted
+/// horizon in slots.
+///
+/// The `*_with_admission_not_atomic` entry points take the pair from the caller; the plain
+/// trade, crank and convert paths apply `V16Config::default_warmup_admission`, so fresh PnL
+/// on those paths is never left unreserved (spec §9.2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WarmupAdmissionV16 {
+    pub admit_h_min: u64,
+    pub admit_h_max: u64,
+}
+
+impl WarmupAdmissionV16 {
+    const fn within(&self, h_min: u64, h_max: u64) -> bool {
+        self.admit_h_max != 0
+            && self.admit_h_min <= self.admit_h_max
+            && self.admit_h_min >= h_min
+            && self.admit_h_max <= h_max
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct WarmupAdmissionScopeV16 {
+    admission: WarmupAdmissionV16,
+    h_max_sticky_accounts: [Option<[u8; 32]>; 2],
+}
+
+impl WarmupAdmissionScopeV16 {
+    fn is_h_max_sticky(&self, portfolio_account_id: [u8; 32]) -> bool {
+        let mut i = 0usize;
+        while i < self.h_max_sticky_accounts.len() {
+            if self.h_max_sticky_accounts[i] == Some(portfolio_account_id) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    fn insert_h_max_sticky(&mut self, portfolio_account_id: [u8; 32]) -> V16Result<()> {
+        if self.is_h_max_sticky(portfolio_account_id) {
+            return Ok(());
+        }
+        let mut i = 0usize;
+        while i < self.h_max_sticky_accounts.len() {
+            if self.h_max_sticky_accounts[i].is_none() {
+                self.h_max_sticky_accounts[i] = Some(portfolio_account_id);
+                return Ok(());
+            }
+            i += 1;
+        }
+        Err(V16Error::CounterOverflow)
+    }
+}
+
+fn validate_warmup_reserve_value(reserve: WarmupReserveV16) -> V16Result<()> {
+    if reserve.sched_remaining == 0 {
+        if reserve.sched_start_slot != 0
+            || reserve.sched_horizon != 0
+            || reserve.sched_anchor != 0
+            || reserve.sched_released != 0
+            || reserve.pending_remaining != 0
+        {
+            return Err(V16Error::InvalidLeg);
+        }
+    } else if reserve.sched_horizon == 0
+        || reserve.sched_released > reserve.sched_anchor
+        || reserve.sched_remaining > reserve.sched_anchor - reserve.sched_released
+    {
+        return Err(V16Error::InvalidLeg);
+    }
+    if (reserve.pending_remaining == 0) != (reserve.pending_horizon == 0) {
+        return Err(V16Error::InvalidLeg);
+    }
+    reserve.total()?;
+    Ok(())
+}
+
+fn promote_pending_warmup_reserve(
+    mut reserve: WarmupReserveV16,
+    current_slot: u64,
+) -> WarmupReserveV16 {
+    if reserve.sched_remaining != 0 || reserve.pending_remaining == 0 {
+        return reserve;
+    }
+    reserve.sched_start_slot = current_slot;
+    reserve.sched_horizon = reserve.pending_horizon;
+    reserve.sched_anchor = reserve.pending_remaining;
+    reserve.sched_remaining = reserve.pending_remaining;
+    reserve.sched_released = 0;
+    reserve.clear_pending();
+    reserve
+}
+
+fn append_warmup_reserve(
+    reserve: WarmupReserveV16,
+    reserve_add: u128,
+    admitted_h_eff: u64,
+    current_slot: u64,
+) -> V16Result<WarmupReserveV16> {
+    if reserve_add == 0 || admitted_h_eff == 0 {
+        return Err(V16Error::InvalidLeg);
+    }
+    let mut reserve = promote_pending_warmup_reserve(reserve, current_slot);
+    if reserve.sched_remaining == 0 {
+        reserve.sched_start_slot = current_slot;
+        reserve.sched_horizon = admitted_h_eff;
+        reserve.sched_anchor = reserve_add;
+        reserve.sched_remaining = reserve_add;
+        reserve.sched_released = 0;
+    } else if reserve.pending_remaining == 0
+        && reserve.sched_start_slot == current_slot
+        && reserve.sched_horizon == admitted_h_eff
+        && reserve.sched_released == 0
+    {
+        reserve.sched_anchor = reserve
+            .sched_anchor
+            .checked_add(reserve_add)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        reserve.sched_remaining = reserve
+            .sched_remaining
+            .checked_add(reserve_add)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+    } else if reserve.pending_remaining == 0 {
+        reserve.pending_remaining = reserve_add;
+        reserve.pending_horizon = admitted_h_eff;
+    } else {
+        reserve.pending_remaining = reserve
+            .pending_remaining
+            .checked_add(reserve_add)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        reserve.pending_horizon = reserve.pending_horizon.max(admitted_h_eff);
+    }
+    validate_warmup_reserve_value(reserve)?;
+    Ok(reserve)
+}
+
+fn apply_warmup_reserve_loss_newest_first(
+    mut reserve: WarmupReserveV16,
+    reserve_loss: u128,
+) -> V16Result<WarmupReserveV16> {
+    if reserve_loss > reserve.total()? {
+        return Err(V16Error::CounterUnderflow);
+    }
+    let pending_loss = reserve_loss.min(reserve.pending_remaining);
+    reserve.pending_remaining -= pending_loss;
+    if reserve.pending_remaining == 0 {
+        reserve.clear_pending();
+    }
+    reserve.sched_remaining -= reserve_loss - pending_loss;
+    if reserve.sched_remaining == 0 {
+        reserve.clear_scheduled();
+    }
+    validate_warmup_reserve_value(reserve)?;
+    Ok(reserve)
+}
+
+/// Returns the advanced reserve and the amount released from it at `current_slot`.
+fn advance_warmup_reserve(
+    reserve: WarmupReserveV16,
+    current_slot: u64,
+) -> V16Result<(WarmupReserveV16, u128)> {
+    let mut reserve = promote_pending_warmup_reserve(reserve, current_slot);
+    if reserve.sched_remaining == 0 {
+        return Ok((reserve, 0));
+    }
+    let elapsed = current_slot
+        .checked_sub(reserve.sched_start_slot)
+        .ok_or(V16Error::InvalidLeg)?;
+    let effective_elapsed = elapsed.min(reserve.sched_horizon);
+    let sched_total = wide_mul_div_floor_u128(
+        reserve.sched_anchor,
+        effective_elapsed as u128,
+        reserve.sched_horizon as u128,
+    );
+    let sched_increment = sched_total.saturating_sub(reserve.sched_released);
+    let release = reserve.sched_remaining.min(sched_increment);
+    reserve.sched_remaining -= release;
+    if reserve.sched_remaining == 0 {
+        reserve.clear_scheduled();
+        reserve = promote_pending_warmup_reserve(reserve, current_slot);
+    } else {
+        reserve.sched_released = sched_total;
+    }
+    validate_warmup_reserve_value(reserve)?;
+    Ok((reserve, release))
+}
+
+#[cfg(kani)]
+pub fn kani_append_warmup_reserve(
+    reserve: WarmupReserveV16,
+    reserve_add: u128,
+    admitted_h_eff: u64,
+    current_slot: u64,
+) -> V16Result<WarmupReserveV16> {
+    append_warmup_reserve(reserve, reserve_add, admitted_h_eff, current_slot)
+}
+
+#[cfg(kani)]
+pub fn kani_advance_warmup_reserve(
+    reserve: WarmupReserveV16,
+    current_slot: u64,
+) -> V16Result<(WarmupReserveV16, u128)> {
+    advance_warmup_reserve(reserve, current_slot)
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AccrueAssetOutcomeV16 {
@@ -3214,6 +4298,15 @@ pub struct AccrueAssetOutcomeV16 {
     pub loss_stale_after: bool,
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OracleCatchupOutcomeV16 {
+    pub segments: u32,
+    pub effective_price: u64,
+    pub slot_last: u64,
+    pub caught_up: bool,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TradeRequestV16 {
@@ -3223,6 +4316,45 @@ pub struct TradeRequestV16 {
     pub size_q: i128,
     pub exec_price: u64,
     pub fee_bps: u64,
+    /// Rejects the fill if it would grow or flip the first account's position.
+    pub reduce_only_a: bool,
+    /// Rejects the fill if it would grow or flip the second account's position.
+    pub reduce_only_b: bool,
+    /// Fee rate charged to the maker; negative pays a rebate out of the taker's fee. Must be zero
+    /// without a maker, in which case `fee_bps` is charged to both accounts.
+    pub maker_fee_bps: i64,
+    /// Account that provided liquidity; the other account pays `fee_bps` as taker.
+    pub maker: TradeMakerV16,
+    /// Rejects the fill unless the first account is the maker.
+    pub post_only_a: bool,
+    /// Rejects the fill unless the second account is the maker.
+    pub post_only_b: bool,
+}
+
+impl TradeRequestV16 {
+    /// Plain fill of `size_q` at `exec_price` with `fee_bps` charged to both accounts: no maker,
+    /// no reduce-only or post-only flags. Set the other fields with struct update syntax.
+    pub const fn new(asset_index: usize, size_q: i128, exec_price: u64, fee_bps: u64) -> Self {
+        Self {
+            asset_index,
+            size_q,
+            exec_price,
+            fee_bps,
+            reduce_only_a: false,
+            reduce_only_b: false,
+            maker_fee_bps: 0,
+            maker: TradeMakerV16::None,
+            post_only_a: false,
+            post_only_b: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeMakerV16 {
+    None,
+    A,
+    B,
 }
 
 #[repr(C)]
@@ -3239,6 +4371,9 @@ pub struct BatchTradeOutcomeV16 {
     pub fill_count: u32,
     pub fee_a: u128,
     pub fee_b: u128,
+    /// Maker rebates paid to each account, out of the taker fee of the same fill.
+    pub rebate_a: u128,
+    pub rebate_b: u128,
     pub notional: u128,
 }
 
@@ -3246,6 +4381,8 @@ pub struct BatchTradeOutcomeV16 {
 struct TradeApplyOutcomeV16 {
     fee_a: u128,
     fee_b: u128,
+    rebate_a: u128,
+    rebate_b: u128,
     notional: u128,
     risk_increasing: bool,
     long_has_source_claims: bool,
@@ -3426,6 +4563,17 @@ impl TokenValueFlowProofV16 {
         Ok(proof)
     }
 
+    pub fn account_capital_to_account_capital(
+        amount: u128,
+        vault_before: u128,
+        vault_after: u128,
+    ) -> V16Result<Self> {
+        let mut proof = Self::empty(vault_before, vault_after);
+        proof.debit(TokenValueClassV16::AccountCapital, amount)?;
+        proof.credit(TokenValueClassV16::AccountCapital, amount)?;
+        Ok(proof)
+    }
+
     fn external_in_to_insurance_capital(
         amount: u128,
         vault_before: u128,
@@ -3450,7 +4598,7 @@ impl TokenValueFlowProofV16 {
         Ok(proof)
     }
 
-    fn insurance_capital_to_account_capital(
+    pub fn insurance_capital_to_account_capital(
         amount: u128,
         vault_before: u128,
         vault_after: u128,
@@ -3461,6 +4609,17 @@ impl TokenValueFlowProofV16 {
         Ok(proof)
     }
 
+    pub fn protocol_surplus_to_insurance_capital(
+        amount: u128,
+        vault_before: u128,
+        vault_after: u128,
+    ) -> V16Result<Self> {
+        let mut proof = Self::empty(vault_before, vault_after);
+        proof.debit(TokenValueClassV16::UnallocatedProtocolSurplus, amount)?;
+        proof.credit(TokenValueClassV16::InsuranceCapital, amount)?;
+        Ok(proof)
+    }
+
     pub fn account_capital_to_realized_loss(
         amount: u128,
         vault_before: u128,
@@ -3758,6 +4917,9 @@ impl SourceCreditLienAggregateProofV16 {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct LiquidationRequestV16 {
     pub asset_index: usize,
+    /// Nonzero quantity to close, clamped to the leg. Keepers that want the minimal restoring
+    /// close size it with `liquidation_close_q_to_restore` on the refreshed account; the
+    /// permissionless crank's `Liquidate` action does so itself and treats this as a cap.
     pub close_q: u128,
     pub fee_bps: u64,
 }
@@ -3772,6 +4934,37 @@ pub struct LiquidationOutcomeV16 {
     pub fee_charged: u128,
 }
 
+/// Post-state of one portfolio in a `simulate_*` result.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulatedPortfolioV16 {
+    pub health_cert: HealthCertV16,
+    pub capital: u128,
+    pub pnl: i128,
+    pub fee_credits: i128,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulatedBatchTradeV16 {
+    pub outcome: BatchTradeOutcomeV16,
+    pub long_account: SimulatedPortfolioV16,
+    pub short_account: SimulatedPortfolioV16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulatedLiquidationV16 {
+    pub outcome: LiquidationOutcomeV16,
+    pub account: SimulatedPortfolioV16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulatedWithdrawV16 {
+    pub account: SimulatedPortfolioV16,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct DeadLegForfeitOutcomeV16 {
@@ -3845,12 +5038,140 @@ pub struct PermissionlessCrankRequestV16 {
     pub action: PermissionlessCrankActionV16,
 }
 
+/// One keeper crank over a single accrued asset. Phase 1 revalidates at most
+/// `max_revalidations` keeper-supplied candidates; Phase 2 touches the next
+/// `rr_window_size` portfolios of the round-robin sweep.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeeperCrankRequestV16 {
+    pub now_slot: u64,
+    pub asset_index: usize,
+    pub effective_price: u64,
+    pub funding_rate_e9: i128,
+    pub admission: WarmupAdmissionV16,
+    pub max_revalidations: u32,
+    pub rr_window_size: u64,
+}
+
+/// Phase 1 candidate. `liquidation` is attempted only when the refreshed certificate
+/// reports a liquidation deficit.
+pub struct KeeperCandidateV16<'a> {
+    pub account: PortfolioV16ViewMut<'a>,
+    pub liquidation: Option<LiquidationRequestV16>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeeperCrankOutcomeV16 {
+    pub revalidated: u32,
+    pub liquidated: u32,
+    /// Requested liquidations passed over because the leg was healthy or its close was locked.
+    pub liquidations_skipped: u32,
+    pub stopped_on_pending_reset: bool,
+    pub swept: u64,
+    pub rr_cursor_position: u64,
+    pub sweep_generation: u64,
+}
+
+/// Explicit resolution branch. `Ordinary` accrues every asset to its live-sync price and
+/// bounds the resolved price by `resolve_price_deviation_bps`; `Degenerate` is the privileged
+/// recovery branch that resolves from `P_last` without accrual or band.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolveModeV16 {
+    Ordinary,
+    Degenerate,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolveAssetPriceV16 {
+    pub resolved_price: u64,
+    pub live_oracle_price: u64,
+    pub funding_rate_e9: i128,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResolvedCloseOutcomeV16 {
     ProgressOnly,
     Closed { payout: u128 },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LienSourceV16 {
+    Counterparty,
+    Insurance,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LienActionV16 {
+    Create,
+    Release,
+    Consume,
+    Impair,
+}
+
+/// Typed record of one value-moving engine step, delivered to an `EventSinkV16`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineEventV16 {
+    /// One fill of a trade or batch; `size_q > 0` makes the long account long.
+    Fill {
+        asset_index: usize,
+        size_q: i128,
+        exec_price: u64,
+        notional: u128,
+        fee_long: u128,
+        fee_short: u128,
+    },
+    Liquidation {
+        asset_index: usize,
+        outcome: LiquidationOutcomeV16,
+    },
+    /// Bankruptcy residual booked against the opposite side's B index (ADL step).
+    ResidualBooked {
+        asset_index: usize,
+        bankrupt_side: SideV16,
+        outcome: BResidualBookingOutcomeV16,
+    },
+    /// K/F index deltas written by one accrual segment.
+    Accrual {
+        asset_index: usize,
+        dt: u64,
+        k_long_delta: i128,
+        k_short_delta: i128,
+        f_long_num_delta: i128,
+        f_short_num_delta: i128,
+    },
+    SideResetBegun {
+        asset_index: usize,
+        side: SideV16,
+    },
+    SideResetFinalized {
+        asset_index: usize,
+        side: SideV16,
+    },
+    /// Insurance paid out of the fund; `domain` is `None` for unbudgeted surplus.
+    InsuranceSpent {
+        domain: Option<usize>,
+        amount: u128,
+    },
+    Lien {
+        domain: usize,
+        source: LienSourceV16,
+        action: LienActionV16,
+        amount: u128,
+    },
+    ResolvedPayout {
+        capital_paid: u128,
+        resolved_paid: u128,
+        payout: u128,
+    },
+}
+
+/// Observer for engine events. The engine never calls a sink unless one is attached with
+/// `MarketGroupV16ViewMut::with_event_sink`, so hosts that do not observe pay nothing.
+pub trait EventSinkV16 {
+    fn on_event(&mut self, _event: EngineEventV16) {}
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct V16PodU16 {
@@ -4011,29 +5332,94 @@ impl ProvenanceHeaderV16Account {
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
-pub struct V16ConfigAccount {
-    pub max_portfolio_assets: V16PodU16,
-    pub max_market_slots: V16PodU32,
-    pub min_nonzero_mm_req: V16PodU128,
-    pub min_nonzero_im_req: V16PodU128,
-    pub h_min: V16PodU64,
-    pub h_max: V16PodU64,
+pub struct FeeTierV16Account {
+    pub volume_threshold: V16PodU128,
+    pub fee_discount_bps: V16PodU64,
+}
+
+impl FeeTierV16Account {
+    pub fn from_runtime(value: &FeeTierV16) -> Self {
+        Self {
+            volume_threshold: V16PodU128::new(value.volume_threshold),
+            fee_discount_bps: V16PodU64::new(value.fee_discount_bps),
+        }
+    }
+
+    pub fn to_runtime(&self) -> FeeTierV16 {
+        FeeTierV16 {
+            volume_threshold: self.volume_threshold.get(),
+            fee_discount_bps: self.fee_discount_bps.get(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct MarginBracketV16Account {
+    pub notional_threshold: V16PodU128,
     pub maintenance_margin_bps: V16PodU64,
     pub initial_margin_bps: V16PodU64,
-    pub max_trading_fee_bps: V16PodU64,
-    pub liquidation_fee_bps: V16PodU64,
-    pub liquidation_fee_cap: V16PodU128,
-    pub min_liquidation_abs: V16PodU128,
-    pub max_accrual_dt_slots: V16PodU64,
-    pub max_abs_funding_e9_per_slot: V16PodU64,
-    pub min_funding_lifetime_slots: V16PodU64,
-    pub max_price_move_bps_per_slot: V16PodU64,
-    pub max_account_b_settlement_chunks: V16PodU64,
-    pub max_bankrupt_close_chunks: V16PodU64,
+}
+
+impl MarginBracketV16Account {
+    pub fn from_runtime(value: &MarginBracketV16) -> Self {
+        Self {
+            notional_threshold: V16PodU128::new(value.notional_threshold),
+            maintenance_margin_bps: V16PodU64::new(value.maintenance_margin_bps),
+            initial_margin_bps: V16PodU64::new(value.initial_margin_bps),
+        }
+    }
+
+    pub fn to_runtime(&self) -> MarginBracketV16 {
+        MarginBracketV16 {
+            notional_threshold: self.notional_threshold.get(),
+            maintenance_margin_bps: self.maintenance_margin_bps.get(),
+            initial_margin_bps: self.initial_margin_bps.get(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct V16ConfigAccount {
+    pub max_portfolio_assets: V16PodU16,
+    pub max_market_slots: V16PodU32,
+    pub min_nonzero_mm_req: V16PodU128,
+    pub min_nonzero_im_req: V16PodU128,
+    pub h_min: V16PodU64,
+    pub h_max: V16PodU64,
+    pub default_admit_h_min: V16PodU64,
+    pub default_admit_h_max: V16PodU64,
+    pub maintenance_margin_bps: V16PodU64,
+    pub initial_margin_bps: V16PodU64,
+    pub max_trading_fee_bps: V16PodU64,
+    pub liquidation_fee_bps: V16PodU64,
+    pub liquidation_fee_cap: V16PodU128,
+    pub min_liquidation_abs: V16PodU128,
+    pub backstop_liquidation_discount_bps: V16PodU64,
+    pub liquidation_auction_start_bps: V16PodU64,
+    pub liquidation_auction_ramp_slots: V16PodU64,
+    pub funding_twap_window_slots: V16PodU64,
+    pub funding_interest_rate_e9_per_slot: V16PodU64,
+    pub funding_premium_clamp_e9_per_slot: V16PodU64,
+    pub funding_mark_band_bps: V16PodU64,
+    pub max_accrual_dt_slots: V16PodU64,
+    pub max_abs_funding_e9_per_slot: V16PodU64,
+    pub funding_spread_e9_per_slot: V16PodU64,
+    pub min_funding_lifetime_slots: V16PodU64,
+    pub max_price_move_bps_per_slot: V16PodU64,
+    pub max_account_b_settlement_chunks: V16PodU64,
+    pub max_bankrupt_close_chunks: V16PodU64,
     pub max_bankrupt_close_lifetime_slots: V16PodU64,
     pub asset_activation_cooldown_slots: V16PodU64,
     pub public_b_chunk_atoms: V16PodU128,
     pub max_recovery_fallback_deviation_bps: V16PodU64,
+    pub resolve_price_deviation_bps: V16PodU64,
+    pub config_update_timelock_slots: V16PodU64,
+    pub config_update_grace_slots: V16PodU64,
+    pub margin_brackets: [MarginBracketV16Account; V16_MAX_MARGIN_BRACKETS],
+    pub fee_tiers: [FeeTierV16Account; V16_MAX_FEE_TIERS],
+    pub fee_volume_window_slots: V16PodU64,
     pub backing_fee_base_rate_e9_per_slot: V16PodU64,
     pub backing_fee_kink_util_bps: V16PodU64,
     pub backing_fee_slope_at_kink_e9_per_slot: V16PodU64,
@@ -4060,14 +5446,30 @@ impl V16ConfigAccount {
             min_nonzero_im_req: V16PodU128::new(value.min_nonzero_im_req),
             h_min: V16PodU64::new(value.h_min),
             h_max: V16PodU64::new(value.h_max),
+            default_admit_h_min: V16PodU64::new(value.default_admit_h_min),
+            default_admit_h_max: V16PodU64::new(value.default_admit_h_max),
             maintenance_margin_bps: V16PodU64::new(value.maintenance_margin_bps),
             initial_margin_bps: V16PodU64::new(value.initial_margin_bps),
             max_trading_fee_bps: V16PodU64::new(value.max_trading_fee_bps),
             liquidation_fee_bps: V16PodU64::new(value.liquidation_fee_bps),
             liquidation_fee_cap: V16PodU128::new(value.liquidation_fee_cap),
             min_liquidation_abs: V16PodU128::new(value.min_liquidation_abs),
+            backstop_liquidation_discount_bps: V16PodU64::new(
+                value.backstop_liquidation_discount_bps,
+            ),
+            liquidation_auction_start_bps: V16PodU64::new(value.liquidation_auction_start_bps),
+            liquidation_auction_ramp_slots: V16PodU64::new(value.liquidation_auction_ramp_slots),
+            funding_twap_window_slots: V16PodU64::new(value.funding_twap_window_slots),
+            funding_interest_rate_e9_per_slot: V16PodU64::new(
+                value.funding_interest_rate_e9_per_slot,
+            ),
+            funding_premium_clamp_e9_per_slot: V16PodU64::new(
+                value.funding_premium_clamp_e9_per_slot,
+            ),
+            funding_mark_band_bps: V16PodU64::new(value.funding_mark_band_bps),
             max_accrual_dt_slots: V16PodU64::new(value.max_accrual_dt_slots),
             max_abs_funding_e9_per_slot: V16PodU64::new(value.max_abs_funding_e9_per_slot),
+            funding_spread_e9_per_slot: V16PodU64::new(value.funding_spread_e9_per_slot),
             min_funding_lifetime_slots: V16PodU64::new(value.min_funding_lifetime_slots),
             max_price_move_bps_per_slot: V16PodU64::new(value.max_price_move_bps_per_slot),
             max_account_b_settlement_chunks: V16PodU64::new(value.max_account_b_settlement_chunks),
@@ -4080,6 +5482,16 @@ impl V16ConfigAccount {
             max_recovery_fallback_deviation_bps: V16PodU64::new(
                 value.max_recovery_fallback_deviation_bps,
             ),
+            resolve_price_deviation_bps: V16PodU64::new(value.resolve_price_deviation_bps),
+            config_update_timelock_slots: V16PodU64::new(value.config_update_timelock_slots),
+            config_update_grace_slots: V16PodU64::new(value.config_update_grace_slots),
+            margin_brackets: value
+                .margin_brackets
+                .map(|bracket| MarginBracketV16Account::from_runtime(&bracket)),
+            fee_tiers: value
+                .fee_tiers
+                .map(|tier| FeeTierV16Account::from_runtime(&tier)),
+            fee_volume_window_slots: V16PodU64::new(value.fee_volume_window_slots),
             backing_fee_base_rate_e9_per_slot: V16PodU64::new(
                 value.backing_fee_base_rate_e9_per_slot,
             ),
@@ -4122,14 +5534,24 @@ impl V16ConfigAccount {
             min_nonzero_im_req: self.min_nonzero_im_req.get(),
             h_min: self.h_min.get(),
             h_max: self.h_max.get(),
+            default_admit_h_min: self.default_admit_h_min.get(),
+            default_admit_h_max: self.default_admit_h_max.get(),
             maintenance_margin_bps: self.maintenance_margin_bps.get(),
             initial_margin_bps: self.initial_margin_bps.get(),
             max_trading_fee_bps: self.max_trading_fee_bps.get(),
             liquidation_fee_bps: self.liquidation_fee_bps.get(),
             liquidation_fee_cap: self.liquidation_fee_cap.get(),
             min_liquidation_abs: self.min_liquidation_abs.get(),
+            backstop_liquidation_discount_bps: self.backstop_liquidation_discount_bps.get(),
+            liquidation_auction_start_bps: self.liquidation_auction_start_bps.get(),
+            liquidation_auction_ramp_slots: self.liquidation_auction_ramp_slots.get(),
+            funding_twap_window_slots: self.funding_twap_window_slots.get(),
+            funding_interest_rate_e9_per_slot: self.funding_interest_rate_e9_per_slot.get(),
+            funding_premium_clamp_e9_per_slot: self.funding_premium_clamp_e9_per_slot.get(),
+            funding_mark_band_bps: self.funding_mark_band_bps.get(),
             max_accrual_dt_slots: self.max_accrual_dt_slots.get(),
             max_abs_funding_e9_per_slot: self.max_abs_funding_e9_per_slot.get(),
+            funding_spread_e9_per_slot: self.funding_spread_e9_per_slot.get(),
             min_funding_lifetime_slots: self.min_funding_lifetime_slots.get(),
             max_price_move_bps_per_slot: self.max_price_move_bps_per_slot.get(),
             max_account_b_settlement_chunks: self.max_account_b_settlement_chunks.get(),
@@ -4138,6 +5560,12 @@ impl V16ConfigAccount {
             asset_activation_cooldown_slots: self.asset_activation_cooldown_slots.get(),
             public_b_chunk_atoms: self.public_b_chunk_atoms.get(),
             max_recovery_fallback_deviation_bps: self.max_recovery_fallback_deviation_bps.get(),
+            resolve_price_deviation_bps: self.resolve_price_deviation_bps.get(),
+            config_update_timelock_slots: self.config_update_timelock_slots.get(),
+            config_update_grace_slots: self.config_update_grace_slots.get(),
+            margin_brackets: self.margin_brackets.map(|bracket| bracket.to_runtime()),
+            fee_tiers: self.fee_tiers.map(|tier| tier.to_runtime()),
+            fee_volume_window_slots: self.fee_volume_window_slots.get(),
             backing_fee_base_rate_e9_per_slot: self.backing_fee_base_rate_e9_per_slot.get(),
             backing_fee_kink_util_bps: self.backing_fee_kink_util_bps.get(),
             backing_fee_slope_at_kink_e9_per_slot: self.backing_fee_slope_at_kink_e9_per_slot.get(),
@@ -4358,6 +5786,14 @@ pub struct AssetStateV16Account {
     pub epoch_short: V16PodU64,
     pub mode_long: u8,
     pub mode_short: u8,
+    pub resolved_slot: V16PodU64,
+    pub resolved_price: V16PodU64,
+    pub funding_mark_price: V16PodU64,
+    pub funding_mark_slot: V16PodU64,
+    pub funding_mark_cum: V16PodU128,
+    pub funding_index_cum: V16PodU128,
+    pub funding_window_slots: V16PodU64,
+    pub funding_spread_unswept: V16PodU128,
 }
 
 impl AssetStateV16Account {
@@ -4404,6 +5840,14 @@ impl AssetStateV16Account {
             epoch_short: V16PodU64::new(value.epoch_short),
             mode_long: encode_side_mode(value.mode_long),
             mode_short: encode_side_mode(value.mode_short),
+            resolved_slot: V16PodU64::new(value.resolved_slot),
+            resolved_price: V16PodU64::new(value.resolved_price),
+            funding_mark_price: V16PodU64::new(value.funding_mark_price),
+            funding_mark_slot: V16PodU64::new(value.funding_mark_slot),
+            funding_mark_cum: V16PodU128::new(value.funding_mark_cum),
+            funding_index_cum: V16PodU128::new(value.funding_index_cum),
+            funding_window_slots: V16PodU64::new(value.funding_window_slots),
+            funding_spread_unswept: V16PodU128::new(value.funding_spread_unswept),
         }
     }
 
@@ -4450,6 +5894,14 @@ impl AssetStateV16Account {
             epoch_short: self.epoch_short.get(),
             mode_long: decode_side_mode(self.mode_long)?,
             mode_short: decode_side_mode(self.mode_short)?,
+            resolved_slot: self.resolved_slot.get(),
+            resolved_price: self.resolved_price.get(),
+            funding_mark_price: self.funding_mark_price.get(),
+            funding_mark_slot: self.funding_mark_slot.get(),
+            funding_mark_cum: self.funding_mark_cum.get(),
+            funding_index_cum: self.funding_index_cum.get(),
+            funding_window_slots: self.funding_window_slots.get(),
+            funding_spread_unswept: self.funding_spread_unswept.get(),
         };
         validate_non_min_i128(out.k_long)?;
         validate_non_min_i128(out.k_short)?;
@@ -4463,6 +5915,74 @@ impl AssetStateV16Account {
     }
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct AssetRiskProfileV16Account {
+    pub enabled: u8,
+    pub maintenance_margin_bps: V16PodU64,
+    pub initial_margin_bps: V16PodU64,
+    pub liquidation_fee_bps: V16PodU64,
+    pub max_price_move_bps_per_slot: V16PodU64,
+    pub max_abs_funding_e9_per_slot: V16PodU64,
+}
+
+impl AssetRiskProfileV16Account {
+    pub fn from_runtime(value: &AssetRiskProfileV16) -> Self {
+        Self {
+            enabled: encode_bool(value.enabled),
+            maintenance_margin_bps: V16PodU64::new(value.maintenance_margin_bps),
+            initial_margin_bps: V16PodU64::new(value.initial_margin_bps),
+            liquidation_fee_bps: V16PodU64::new(value.liquidation_fee_bps),
+            max_price_move_bps_per_slot: V16PodU64::new(value.max_price_move_bps_per_slot),
+            max_abs_funding_e9_per_slot: V16PodU64::new(value.max_abs_funding_e9_per_slot),
+        }
+    }
+
+    pub fn try_to_runtime(&self) -> V16Result<AssetRiskProfileV16> {
+        Ok(AssetRiskProfileV16 {
+            enabled: decode_bool(self.enabled)?,
+            maintenance_margin_bps: self.maintenance_margin_bps.get(),
+            initial_margin_bps: self.initial_margin_bps.get(),
+            liquidation_fee_bps: self.liquidation_fee_bps.get(),
+            max_price_move_bps_per_slot: self.max_price_move_bps_per_slot.get(),
+            max_abs_funding_e9_per_slot: self.max_abs_funding_e9_per_slot.get(),
+        })
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct PortfolioMarginOffsetV16Account {
+    pub asset_a: V16PodU32,
+    pub asset_b: V16PodU32,
+    pub offset_bps: V16PodU64,
+}
+
+impl PortfolioMarginOffsetV16Account {
+    pub fn from_runtime(value: &PortfolioMarginOffsetV16) -> Self {
+        Self {
+            asset_a: V16PodU32::new(value.asset_a),
+            asset_b: V16PodU32::new(value.asset_b),
+            offset_bps: V16PodU64::new(value.offset_bps),
+        }
+    }
+
+    pub fn try_to_runtime(&self) -> V16Result<PortfolioMarginOffsetV16> {
+        let value = PortfolioMarginOffsetV16 {
+            asset_a: self.asset_a.get(),
+            asset_b: self.asset_b.get(),
+            offset_bps: self.offset_bps.get(),
+        };
+        if value.offset_bps > MAX_MARGIN_BPS
+            || (value.offset_bps == 0 && value != PortfolioMarginOffsetV16::EMPTY)
+            || (value.offset_bps != 0 && value.asset_a >= value.asset_b)
+        {
+            return Err(V16Error::InvalidConfig);
+        }
+        Ok(value)
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct EngineAssetSlotV16Account {
@@ -4479,6 +5999,7 @@ pub struct EngineAssetSlotV16Account {
     pub backing_short: BackingBucketV16Account,
     pub insurance_reservation_long: InsuranceCreditReservationV16Account,
     pub insurance_reservation_short: InsuranceCreditReservationV16Account,
+    pub risk_profile: AssetRiskProfileV16Account,
 }
 
 fn asset_contributes_to_loss_stale_summary(asset: AssetStateV16) -> bool {
@@ -4589,6 +6110,7 @@ impl EngineAssetSlotV16Account {
             insurance_reservation_short: InsuranceCreditReservationV16Account::from_runtime(
                 &InsuranceCreditReservationV16::EMPTY,
             ),
+            risk_profile: AssetRiskProfileV16Account::default(),
         }
     }
 
@@ -4762,6 +6284,13 @@ pub struct MarketGroupV16HeaderAccount {
     pub payout_snapshot_pnl_pos_tot: V16PodU128,
     pub payout_snapshot_captured: u8,
     pub resolved_payout_ledger: ResolvedPayoutLedgerV16Account,
+    // Keeper round-robin sweep over the host's materialized portfolio list. The cursor wraps at
+    // `materialized_portfolio_count`; each wrap starts a new sweep generation.
+    pub rr_cursor_position: V16PodU64,
+    pub sweep_generation: V16PodU64,
+    pub pending_config_update: PendingConfigUpdateV16Account,
+    pub portfolio_margin_offsets:
+        [PortfolioMarginOffsetV16Account; V16_MAX_PORTFOLIO_MARGIN_OFFSETS],
 }
 
 impl Default for MarketGroupV16HeaderAccount {
@@ -4770,6 +6299,269 @@ impl Default for MarketGroupV16HeaderAccount {
     }
 }
 
+/// Governance-controlled config fields that change only through the propose → wait → activate
+/// pipeline on the group header.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigUpdateV16 {
+    pub maintenance_margin_bps: u64,
+    pub initial_margin_bps: u64,
+    pub h_min: u64,
+    pub h_max: u64,
+    pub max_price_move_bps_per_slot: u64,
+    pub max_abs_funding_e9_per_slot: u64,
+    pub backing_fee_base_rate_e9_per_slot: u64,
+    pub backing_fee_kink_util_bps: u64,
+    pub backing_fee_slope_at_kink_e9_per_slot: u64,
+    pub backing_fee_slope_above_kink_e9_per_slot: u64,
+    pub margin_brackets: [MarginBracketV16; V16_MAX_MARGIN_BRACKETS],
+    pub fee_tiers: [FeeTierV16; V16_MAX_FEE_TIERS],
+    pub fee_volume_window_slots: u64,
+    pub asset_risk_profile: Option<AssetRiskProfileUpdateV16>,
+    /// Replacement portfolio-margin offset table; `None` keeps the current table.
+    pub portfolio_margin_offsets:
+        Option<[PortfolioMarginOffsetV16; V16_MAX_PORTFOLIO_MARGIN_OFFSETS]>,
+}
+
+/// Risk profile to install on one asset when its `ConfigUpdateV16` activates. A disabled
+/// (all-zero) profile clears the override.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssetRiskProfileUpdateV16 {
+    pub asset_index: u32,
+    pub profile: AssetRiskProfileV16,
+}
+
+impl ConfigUpdateV16 {
+    /// The no-op update: every governed field at its current value in `config`.
+    pub fn from_config(config: &V16Config) -> Self {
+        Self {
+            maintenance_margin_bps: config.maintenance_margin_bps,
+            initial_margin_bps: config.initial_margin_bps,
+            h_min: config.h_min,
+            h_max: config.h_max,
+            max_price_move_bps_per_slot: config.max_price_move_bps_per_slot,
+            max_abs_funding_e9_per_slot: config.max_abs_funding_e9_per_slot,
+            backing_fee_base_rate_e9_per_slot: config.backing_fee_base_rate_e9_per_slot,
+            backing_fee_kink_util_bps: config.backing_fee_kink_util_bps,
+            backing_fee_slope_at_kink_e9_per_slot: config.backing_fee_slope_at_kink_e9_per_slot,
+            backing_fee_slope_above_kink_e9_per_slot: config
+                .backing_fee_slope_above_kink_e9_per_slot,
+            margin_brackets: config.margin_brackets,
+            fee_tiers: config.fee_tiers,
+            fee_volume_window_slots: config.fee_volume_window_slots,
+            asset_risk_profile: None,
+            portfolio_margin_offsets: None,
+        }
+    }
+
+    /// The default warmup admission pair is clamped into the new `[h_min, h_max]`.
+    pub fn apply_to(self, mut config: V16Config) -> V16Config {
+        config.maintenance_margin_bps = self.maintenance_margin_bps;
+        config.initial_margin_bps = self.initial_margin_bps;
+        config.h_min = self.h_min;
+        config.h_max = self.h_max;
+        config.default_admit_h_min = config.default_admit_h_min.max(self.h_min).min(self.h_max);
+        config.default_admit_h_max = config.default_admit_h_max.max(self.h_min).min(self.h_max);
+        config.max_price_move_bps_per_slot = self.max_price_move_bps_per_slot;
+        config.max_abs_funding_e9_per_slot = self.max_abs_funding_e9_per_slot;
+        config.backing_fee_base_rate_e9_per_slot = self.backing_fee_base_rate_e9_per_slot;
+        config.backing_fee_kink_util_bps = self.backing_fee_kink_util_bps;
+        config.backing_fee_slope_at_kink_e9_per_slot = self.backing_fee_slope_at_kink_e9_per_slot;
+        config.backing_fee_slope_above_kink_e9_per_slot =
+            self.backing_fee_slope_above_kink_e9_per_slot;
+        config.margin_brackets = self.margin_brackets;
+        config.fee_tiers = self.fee_tiers;
+        config.fee_volume_window_slots = self.fee_volume_window_slots;
+        config
+    }
+
+    /// True when the group-config part of the update can turn a currently certified account
+    /// unhealthy; such updates wait `config_update_grace_slots` past the timelock. See
+    /// `V16Config::tightens_margin_from`. Asset risk profiles are compared on the market view.
+    pub fn tightens_margin(self, config: &V16Config) -> bool {
+        self.apply_to(*config).tightens_margin_from(config)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingConfigUpdateV16 {
+    pub update: ConfigUpdateV16,
+    pub proposed_slot: u64,
+    pub activation_slot: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct PendingConfigUpdateV16Account {
+    pub present: u8,
+    pub proposed_slot: V16PodU64,
+    pub activation_slot: V16PodU64,
+    pub maintenance_margin_bps: V16PodU64,
+    pub initial_margin_bps: V16PodU64,
+    pub h_min: V16PodU64,
+    pub h_max: V16PodU64,
+    pub max_price_move_bps_per_slot: V16PodU64,
+    pub max_abs_funding_e9_per_slot: V16PodU64,
+    pub backing_fee_base_rate_e9_per_slot: V16PodU64,
+    pub backing_fee_kink_util_bps: V16PodU64,
+    pub backing_fee_slope_at_kink_e9_per_slot: V16PodU64,
+    pub backing_fee_slope_above_kink_e9_per_slot: V16PodU64,
+    pub margin_brackets: [MarginBracketV16Account; V16_MAX_MARGIN_BRACKETS],
+    pub fee_tiers: [FeeTierV16Account; V16_MAX_FEE_TIERS],
+    pub fee_volume_window_slots: V16PodU64,
+    pub asset_risk_profile_present: u8,
+    pub asset_risk_profile_index: V16PodU32,
+    pub asset_risk_profile: AssetRiskProfileV16Account,
+    pub portfolio_margin_offsets_present: u8,
+    pub portfolio_margin_offsets:
+        [PortfolioMarginOffsetV16Account; V16_MAX_PORTFOLIO_MARGIN_OFFSETS],
+}
+
+impl PendingConfigUpdateV16Account {
+    pub fn from_runtime(value: Option<PendingConfigUpdateV16>) -> Self {
+        let Some(pending) = value else {
+            return Self::default();
+        };
+        let update = pending.update;
+        let mut margin_brackets = [MarginBracketV16Account::default(); V16_MAX_MARGIN_BRACKETS];
+        let mut i = 0usize;
+        while i < V16_MAX_MARGIN_BRACKETS {
+            margin_brackets[i] = MarginBracketV16Account::from_runtime(&update.margin_brackets[i]);
+            i += 1;
+        }
+        let mut fee_tiers = [FeeTierV16Account::default(); V16_MAX_FEE_TIERS];
+        let mut i = 0usize;
+        while i < V16_MAX_FEE_TIERS {
+            fee_tiers[i] = FeeTierV16Account::from_runtime(&update.fee_tiers[i]);
+            i += 1;
+        }
+        let mut portfolio_margin_offsets =
+            [PortfolioMarginOffsetV16Account::default(); V16_MAX_PORTFOLIO_MARGIN_OFFSETS];
+        if let Some(offsets) = update.portfolio_margin_offsets {
+            let mut i = 0usize;
+            while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+                portfolio_margin_offsets[i] =
+                    PortfolioMarginOffsetV16Account::from_runtime(&offsets[i]);
+                i += 1;
+            }
+        }
+        Self {
+            present: 1,
+            proposed_slot: V16PodU64::new(pending.proposed_slot),
+            activation_slot: V16PodU64::new(pending.activation_slot),
+            maintenance_margin_bps: V16PodU64::new(update.maintenance_margin_bps),
+            initial_margin_bps: V16PodU64::new(update.initial_margin_bps),
+            h_min: V16PodU64::new(update.h_min),
+            h_max: V16PodU64::new(update.h_max),
+            max_price_move_bps_per_slot: V16PodU64::new(update.max_price_move_bps_per_slot),
+            max_abs_funding_e9_per_slot: V16PodU64::new(update.max_abs_funding_e9_per_slot),
+            backing_fee_base_rate_e9_per_slot: V16PodU64::new(
+                update.backing_fee_base_rate_e9_per_slot,
+            ),
+            backing_fee_kink_util_bps: V16PodU64::new(update.backing_fee_kink_util_bps),
+            backing_fee_slope_at_kink_e9_per_slot: V16PodU64::new(
+                update.backing_fee_slope_at_kink_e9_per_slot,
+            ),
+            backing_fee_slope_above_kink_e9_per_slot: V16PodU64::new(
+                update.backing_fee_slope_above_kink_e9_per_slot,
+            ),
+            margin_brackets,
+            fee_tiers,
+            fee_volume_window_slots: V16PodU64::new(update.fee_volume_window_slots),
+            asset_risk_profile_present: encode_bool(update.asset_risk_profile.is_some()),
+            asset_risk_profile_index: V16PodU32::new(
+                update.asset_risk_profile.map_or(0, |u| u.asset_index),
+            ),
+            asset_risk_profile: AssetRiskProfileV16Account::from_runtime(
+                &update
+                    .asset_risk_profile
+                    .map_or(AssetRiskProfileV16::EMPTY, |u| u.profile),
+            ),
+            portfolio_margin_offsets_present: encode_bool(
+                update.portfolio_margin_offsets.is_some(),
+            ),
+            portfolio_margin_offsets,
+        }
+    }
+
+    pub fn try_to_runtime(&self) -> V16Result<Option<PendingConfigUpdateV16>> {
+        let mut margin_brackets = [MarginBracketV16::EMPTY; V16_MAX_MARGIN_BRACKETS];
+        let mut i = 0usize;
+        while i < V16_MAX_MARGIN_BRACKETS {
+            margin_brackets[i] = self.margin_brackets[i].to_runtime();
+            i += 1;
+        }
+        let mut fee_tiers = [FeeTierV16::EMPTY; V16_MAX_FEE_TIERS];
+        let mut i = 0usize;
+        while i < V16_MAX_FEE_TIERS {
+            fee_tiers[i] = self.fee_tiers[i].to_runtime();
+            i += 1;
+        }
+        let asset_risk_profile = if decode_bool(self.asset_risk_profile_present)? {
+            Some(AssetRiskProfileUpdateV16 {
+                asset_index: self.asset_risk_profile_index.get(),
+                profile: self.asset_risk_profile.try_to_runtime()?,
+            })
+        } else {
+            if self.asset_risk_profile_index.get() != 0
+                || self.asset_risk_profile != AssetRiskProfileV16Account::default()
+            {
+                return Err(V16Error::InvalidConfig);
+            }
+            None
+        };
+        let mut offsets = [PortfolioMarginOffsetV16::EMPTY; V16_MAX_PORTFOLIO_MARGIN_OFFSETS];
+        let mut i = 0usize;
+        while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            offsets[i] = self.portfolio_margin_offsets[i].try_to_runtime()?;
+            i += 1;
+        }
+        let portfolio_margin_offsets = if decode_bool(self.portfolio_margin_offsets_present)? {
+            Some(offsets)
+        } else {
+            if offsets != [PortfolioMarginOffsetV16::EMPTY; V16_MAX_PORTFOLIO_MARGIN_OFFSETS] {
+                return Err(V16Error::InvalidConfig);
+            }
+            None
+        };
+        match self.present {
+            0 if *self == Self::default() => Ok(None),
+            1 if self.activation_slot.get() >= self.proposed_slot.get() => {
+                Ok(Some(PendingConfigUpdateV16 {
+                    update: ConfigUpdateV16 {
+                        maintenance_margin_bps: self.maintenance_margin_bps.get(),
+                        initial_margin_bps: self.initial_margin_bps.get(),
+                        h_min: self.h_min.get(),
+                        h_max: self.h_max.get(),
+                        max_price_move_bps_per_slot: self.max_price_move_bps_per_slot.get(),
+                        max_abs_funding_e9_per_slot: self.max_abs_funding_e9_per_slot.get(),
+                        backing_fee_base_rate_e9_per_slot: self
+                            .backing_fee_base_rate_e9_per_slot
+                            .get(),
+                        backing_fee_kink_util_bps: self.backing_fee_kink_util_bps.get(),
+                        backing_fee_slope_at_kink_e9_per_slot: self
+                            .backing_fee_slope_at_kink_e9_per_slot
+                            .get(),
+                        backing_fee_slope_above_kink_e9_per_slot: self
+                            .backing_fee_slope_above_kink_e9_per_slot
+                            .get(),
+                        margin_brackets,
+                        fee_tiers,
+                        fee_volume_window_slots: self.fee_volume_window_slots.get(),
+                        asset_risk_profile,
+                        portfolio_margin_offsets,
+                    },
+                    proposed_slot: self.proposed_slot.get(),
+                    activation_slot: self.activation_slot.get(),
+                }))
+            }
+            _ => Err(V16Error::InvalidConfig),
+        }
+    }
+}
+
 /// fork feature A-9 (dynamic-trade-fee): engine-level fee-policy update payload. The wrapper-side
 /// admin auth / signer / replay-nonce checks land in Phase 3; the engine surface stays admin-agnostic.
 /// v16 already validates per-call `fee_bps` against `config.max_trading_fee_bps` in trade validation;
@@ -4903,6 +6695,11 @@ impl MarketGroupV16HeaderAccount {
             resolved_payout_ledger: ResolvedPayoutLedgerV16Account::from_runtime(
                 &ResolvedPayoutLedgerV16::EMPTY,
             ),
+            rr_cursor_position: V16PodU64::default(),
+            sweep_generation: V16PodU64::default(),
+            pending_config_update: PendingConfigUpdateV16Account::default(),
+            portfolio_margin_offsets: [PortfolioMarginOffsetV16Account::default();
+                V16_MAX_PORTFOLIO_MARGIN_OFFSETS],
         })
     }
 
@@ -5199,6 +6996,7 @@ impl MarketGroupV16HeaderAccount {
             insurance_reservation_short: InsuranceCreditReservationV16Account::from_runtime(
                 &InsuranceCreditReservationV16::EMPTY,
             ),
+            risk_profile: AssetRiskProfileV16Account::default(),
         };
         self.next_market_id = V16PodU64::new(next_market_id);
         self.current_slot = V16PodU64::new(now_slot);
@@ -5254,6 +7052,12 @@ impl<'a, T> MarketGroupV16View<'a, T> {
         decode_bool(self.header.loss_stale_active)?;
         decode_bool(self.header.payout_snapshot_captured)?;
         self.header.recovery_reason.try_to_runtime()?;
+        self.header.pending_config_update.try_to_runtime()?;
+        let mut offset = 0usize;
+        while offset < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            self.header.portfolio_margin_offsets[offset].try_to_runtime()?;
+            offset += 1;
+        }
         let resolved_ledger = self.header.resolved_payout_ledger.try_to_runtime()?;
         if self.header.vault.get() > MAX_VAULT_TVL {
             return Err(V16Error::InvalidConfig);
@@ -5515,7 +7319,17 @@ impl<'a, T> MarketGroupV16View<'a, T> {
             || asset.f_epoch_start_short_num == i128::MIN
             || asset.oi_eff_long_q > crate::MAX_OI_SIDE_Q
             || asset.oi_eff_short_q > crate::MAX_OI_SIDE_Q
-            || (mode == MarketModeV16::Live && asset.oi_eff_long_q != asset.oi_eff_short_q)
+            || (mode == MarketModeV16::Live
+                && asset.resolved_slot == 0
+                && asset.oi_eff_long_q != asset.oi_eff_short_q)
+            || (asset.resolved_slot == 0 && asset.resolved_price != 0)
+            || (asset.resolved_slot != 0
+                && (!matches!(
+                    asset.lifecycle,
+                    AssetLifecycleV16::DrainOnly | AssetLifecycleV16::Retired
+                ) || asset.resolved_slot > current_slot
+                    || asset.resolved_price == 0
+                    || asset.resolved_price > MAX_ORACLE_PRICE))
             || asset.loss_weight_sum_long > SOCIAL_LOSS_DEN
             || asset.loss_weight_sum_short > SOCIAL_LOSS_DEN
             || (asset.oi_eff_long_q != 0 && asset.loss_weight_sum_long == 0)
@@ -5559,18 +7373,119 @@ impl<'a, T> MarketGroupV16View<'a, T> {
     }
 }
 
+/// Side-effect-free previews of the public mutators.
+///
+/// Each `simulate_*` call runs the real `_not_atomic` path against a scratch copy of the group
+/// header, the engine asset slots and the supplied portfolios, then certifies the post-state of
+/// every involved portfolio at the scratch effective prices. Wrapper-owned `Market<T>::wrapper`
+/// bytes are never read by the engine and are not copied. The caller's accounts are untouched
+/// whether the simulated operation succeeds or fails. A failed simulation returns the same
+/// [`V16Rejection`] the live call would record.
+impl<'a, T> MarketGroupV16View<'a, T> {
+    fn simulation_scratch(&self) -> (MarketGroupV16HeaderAccount, Vec<Market<()>>) {
+        let mut markets = Vec::with_capacity(self.markets.len());
+        let mut i = 0usize;
+        while i < self.markets.len() {
+            markets.push(Market::new((), self.markets[i].engine));
+            i += 1;
+        }
+        (*self.header, markets)
+    }
+
+    fn simulated_portfolio(
+        market: &MarketGroupV16ViewMut<'_, ()>,
+        account: &PortfolioV16ViewMut<'_>,
+    ) -> V16Result<SimulatedPortfolioV16> {
+        Ok(SimulatedPortfolioV16 {
+            health_cert: market.compute_account_health_cert_with_price_override(
+                &account.as_view(),
+                false,
+                None,
+            )?,
+            capital: account.header.capital.get(),
+            pnl: account.header.pnl.get(),
+            fee_credits: account.header.fee_credits.get(),
+        })
+    }
+
+    pub fn simulate_execute_batch_with_fee_loss_stale_scoped(
+        &self,
+        long_account: &PortfolioV16View<'_>,
+        short_account: &PortfolioV16View<'_>,
+        requests: &[TradeRequestV16],
+    ) -> Result<SimulatedBatchTradeV16, V16Rejection> {
+        let (mut header, mut markets) = self.simulation_scratch();
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut long_header = *long_account.header;
+        let mut short_header = *short_account.header;
+        let mut long = PortfolioV16ViewMut::new(&mut long_header);
+        let mut short = PortfolioV16ViewMut::new(&mut short_header);
+        let outcome = market.call_with_reason(|m| {
+            m.execute_batch_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, requests)
+        })?;
+        Ok(SimulatedBatchTradeV16 {
+            outcome,
+            long_account: Self::simulated_portfolio(&market, &long)?,
+            short_account: Self::simulated_portfolio(&market, &short)?,
+        })
+    }
+
+    pub fn simulate_liquidate_account(
+        &self,
+        account: &PortfolioV16View<'_>,
+        request: LiquidationRequestV16,
+    ) -> Result<SimulatedLiquidationV16, V16Rejection> {
+        let (mut header, mut markets) = self.simulation_scratch();
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut account_header = *account.header;
+        let mut account = PortfolioV16ViewMut::new(&mut account_header);
+        let outcome =
+            market.call_with_reason(|m| m.liquidate_account_not_atomic(&mut account, request))?;
+        Ok(SimulatedLiquidationV16 {
+            outcome,
+            account: Self::simulated_portfolio(&market, &account)?,
+        })
+    }
+
+    pub fn simulate_withdraw(
+        &self,
+        account: &PortfolioV16View<'_>,
+        amount: u128,
+    ) -> Result<SimulatedWithdrawV16, V16Rejection> {
+        let (mut header, mut markets) = self.simulation_scratch();
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut account_header = *account.header;
+        let mut account = PortfolioV16ViewMut::new(&mut account_header);
+        market.call_with_reason(|m| m.withdraw_not_atomic(&mut account, amount))?;
+        Ok(SimulatedWithdrawV16 {
+            account: Self::simulated_portfolio(&market, &account)?,
+        })
+    }
+}
+
 impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     pub fn validate_shape(&self) -> V16Result<()> {
         self.as_view().validate_shape()
     }
 
+    /// Counts an empty portfolio as materialized without binding it to a list position. Such a
+    /// portfolio cannot be swept by the keeper crank; use
+    /// `register_empty_materialized_portfolio_indexed_not_atomic` for that.
     pub fn register_empty_materialized_portfolio_not_atomic(
         &mut self,
         account: &PortfolioV16View<'_>,
     ) -> V16Result<()> {
         account.validate_with_market(&self.as_view())?;
         if !account.is_empty_for_dematerialization()? {
-            return Err(V16Error::LockActive);
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::MaterializedPortfolioNotEmpty),
+            ));
+        }
+        if decode_bool(account.header.materialized)? {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::MaterializedPortfolioAlreadyIndexed),
+            ));
         }
         self.header.materialized_portfolio_count = V16PodU64::new(
             self.header
@@ -5582,13 +7497,24 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.validate_shape_audit_scan()
     }
 
+    /// Uncounts an empty portfolio counted by `register_empty_materialized_portfolio_not_atomic`.
+    /// A portfolio stamped with a list position must leave through
+    /// `deregister_empty_materialized_portfolio_indexed_not_atomic`.
     pub fn deregister_empty_materialized_portfolio_not_atomic(
         &mut self,
         account: &PortfolioV16View<'_>,
     ) -> V16Result<()> {
         account.validate_with_market(&self.as_view())?;
         if !account.is_empty_for_dematerialization()? {
-            return Err(V16Error::LockActive);
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::MaterializedPortfolioNotEmpty),
+            ));
+        }
+        if decode_bool(account.header.materialized)? {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::MaterializedPortfolioAlreadyIndexed),
+            ));
         }
         self.header.materialized_portfolio_count = V16PodU64::new(
             self.header
@@ -5600,6 +7526,86 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.validate_shape_audit_scan()
     }
 
+    /// Appends an empty portfolio to the materialized list, stamping it with the next index so
+    /// the keeper crank can bind its sweep to it.
+    pub fn register_empty_materialized_portfolio_indexed_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+    ) -> V16Result<()> {
+        account.validate_with_market(&self.as_view())?;
+        if !account.as_view().is_empty_for_dematerialization()? {
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::MaterializedPortfolioNotEmpty),
+            ));
+        }
+        if decode_bool(account.header.materialized)? {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::MaterializedPortfolioAlreadyIndexed),
+            ));
+        }
+        let index = self.header.materialized_portfolio_count.get();
+        self.header.materialized_portfolio_count =
+            V16PodU64::new(index.checked_add(1).ok_or(V16Error::CounterOverflow)?);
+        account.header.materialized = encode_bool(true);
+        account.header.materialized_index = V16PodU64::new(index);
+        self.validate_shape_audit_scan()
+    }
+
+    /// Removes an empty portfolio from the materialized list by swap-remove: unless `account`
+    /// holds the last index, `last` must be the portfolio at the last index, which takes over
+    /// `account`'s index.
+    pub fn deregister_empty_materialized_portfolio_indexed_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        last: Option<&mut PortfolioV16ViewMut<'_>>,
+    ) -> V16Result<()> {
+        account.validate_with_market(&self.as_view())?;
+        if !account.as_view().is_empty_for_dematerialization()? {
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::MaterializedPortfolioNotEmpty),
+            ));
+        }
+        if !decode_bool(account.header.materialized)? {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::MaterializedPortfolioNotIndexed),
+            ));
+        }
+        let last_index = self
+            .header
+            .materialized_portfolio_count
+            .get()
+            .checked_sub(1)
+            .ok_or(V16Error::CounterUnderflow)?;
+        let index = account.header.materialized_index.get();
+        match last {
+            None if index == last_index => {}
+            Some(last) if index != last_index => {
+                last.validate_with_market(&self.as_view())?;
+                if !decode_bool(last.header.materialized)?
+                    || last.header.materialized_index.get() != last_index
+                {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig
+                            .with_reason(V16ErrorReason::MaterializedLastPortfolioMismatch),
+                    ));
+                }
+                last.header.materialized_index = V16PodU64::new(index);
+            }
+            _ => {
+                return Err(self.reject(
+                    V16Error::InvalidConfig
+                        .with_reason(V16ErrorReason::MaterializedLastPortfolioMismatch),
+                ));
+            }
+        }
+        self.header.materialized_portfolio_count = V16PodU64::new(last_index);
+        account.header.materialized = encode_bool(false);
+        account.header.materialized_index = V16PodU64::default();
+        self.validate_shape_audit_scan()
+    }
+
     #[cfg(kani)]
     pub fn refresh_header_aggregate_totals_for_test(&mut self) -> V16Result<()> {
         let totals = self
@@ -5658,6 +7664,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if account.header.reserved_pnl.get() > pnl.max(0) as u128 {
             return Err(V16Error::InvalidLeg);
         }
+        let warmup_reserve = account.header.warmup_reserve.try_to_runtime()?;
+        validate_warmup_reserve_value(warmup_reserve)?;
+        if warmup_reserve.total()? != account.header.reserved_pnl.get() {
+            return Err(V16Error::InvalidLeg);
+        }
         if account.header.residual_spent_principal_atoms_total.get()
             > account.header.residual_crystallized_loss_atoms_total.get()
         {
@@ -6268,8 +8279,14 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_backing_bucket_for_domain(domain, bucket)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
-        Ok(())
-    }
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Counterparty,
+            action: LienActionV16::Create,
+            amount,
+        });
+        Ok(())
+    }
 
     #[cfg(any(kani, feature = "fuzz"))]
     pub fn release_source_credit_lien_from_counterparty_not_atomic(
@@ -6301,6 +8318,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_backing_bucket_for_domain(domain, bucket)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Counterparty,
+            action: LienActionV16::Release,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -6337,6 +8360,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_backing_bucket_for_domain(domain, bucket)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Counterparty,
+            action: LienActionV16::Release,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -6369,6 +8398,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_backing_bucket_for_domain(domain, bucket)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Counterparty,
+            action: LienActionV16::Consume,
+            amount,
+        });
         Ok(())
     }
 
@@ -6411,6 +8446,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_backing_bucket_for_domain(domain, bucket)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Counterparty,
+            action: LienActionV16::Impair,
+            amount,
+        });
         Ok(())
     }
 
@@ -6523,6 +8564,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_insurance_reservation_for_domain(domain, reservation)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Insurance,
+            action: LienActionV16::Create,
+            amount,
+        });
         Ok(())
     }
 
@@ -6555,6 +8602,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_insurance_reservation_for_domain(domain, reservation)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Insurance,
+            action: LienActionV16::Release,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -6586,6 +8639,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_insurance_reservation_for_domain(domain, reservation)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Insurance,
+            action: LienActionV16::Release,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -6635,6 +8694,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.header.insurance = V16PodU128::new(next_insurance);
         self.set_domain_insurance_spent_core(domain, next_domain_spent)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Insurance,
+            action: LienActionV16::Consume,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -6667,6 +8732,12 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.set_insurance_reservation_for_domain(domain, reservation)?;
         self.set_source_credit_for_domain(domain, source)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::Lien {
+            domain,
+            source: LienSourceV16::Insurance,
+            action: LienActionV16::Impair,
+            amount,
+        });
         Ok(())
     }
 
@@ -6826,7 +8897,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 .checked_sub(total_fee)
                 .ok_or(V16Error::CounterUnderflow)?,
         );
-        account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+        self.store_health_cert(account, &cert)?;
 
         if provider_fee != 0 {
             let mut bucket = self.backing_bucket_for_domain(provider_domain)?;
@@ -7713,6 +9784,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         TokenValueFlowProofV16::insurance_capital_to_account_capital(amount, vault, vault)?
             .validate()?;
         account.validate_with_market(&self.as_view())?;
+        self.emit(EngineEventV16::InsuranceSpent {
+            domain: None,
+            amount,
+        });
         self.validate_shape()
     }
 
@@ -7791,6 +9866,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             self.header.vault.get(),
         )?;
         account.header.health_cert.valid = 0;
+        self.emit(EngineEventV16::InsuranceSpent {
+            domain: Some(domain),
+            amount: used,
+        });
         Ok(used)
     }
 
@@ -7932,6 +10011,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.header.insurance = V16PodU128::new(next_insurance);
         self.set_domain_insurance_spent_core(domain, next_domain_spent)?;
         self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.emit(EngineEventV16::InsuranceSpent {
+            domain: Some(domain),
+            amount: spend_atoms,
+        });
         Ok(())
     }
 
@@ -8549,12 +10632,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         abs_q: u128,
         price: u64,
     ) -> V16Result<u128> {
-        let notional = risk_notional_ceil(abs_q, price)?;
-        margin_requirement(
-            notional,
-            config.initial_margin_bps,
-            config.min_nonzero_im_req,
-        )
+        config.initial_requirement(risk_notional_ceil(abs_q, price)?)
     }
 
     fn increased_initial_margin_principal(
@@ -8578,7 +10656,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         trade_preflight: &TradePositionPreflightV16,
         asset_index: usize,
     ) -> V16Result<()> {
-        let config = self.header.config.try_to_runtime_shape()?;
+        let config =
+            self.asset_risk_config(self.header.config.try_to_runtime_shape()?, asset_index)?;
         let price = self.asset_state(asset_index)?.effective_price;
         let long_principal = Self::increased_initial_margin_principal(
             config,
@@ -8664,7 +10743,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     ) -> V16Result<()> {
         account.validate_with_market(&self.as_view())?;
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         if amount == 0 {
             return Ok(());
@@ -8745,6 +10826,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 self.set_source_credit_for_domain(domain, source_credit)?;
                 self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
             }
+            if increase != 0 {
+                self.admit_positive_pnl_increase(account, increase)?;
+            }
         } else {
             let decrease = old_pos - new_pos;
             let decrease_num = V16Core::bound_num_from_amount(decrease)?;
@@ -8763,10 +10847,25 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 .saturating_sub(decrease_num);
             let exact_min_num = V16Core::bound_num_from_amount(self.header.pnl_pos_tot.get())?;
             self.header.pnl_pos_bound_tot_num = V16PodU128::new(next_bound_num.max(exact_min_num));
+            // Losses consume reserved PnL newest-first before touching matured PnL.
+            let reserved = account.header.reserved_pnl.get();
+            let reserve_loss = reserved.saturating_sub(new_pos);
+            if reserve_loss != 0 {
+                let reserve = apply_warmup_reserve_loss_newest_first(
+                    account.header.warmup_reserve.try_to_runtime()?,
+                    reserve_loss,
+                )?;
+                account.header.warmup_reserve = WarmupReserveV16Account::from_runtime(&reserve);
+                account.header.reserved_pnl = V16PodU128::new(reserved - reserve_loss);
+            }
+            let matured_loss = decrease
+                .checked_sub(reserve_loss)
+                .ok_or(V16Error::CounterUnderflow)?;
             self.header.pnl_matured_pos_tot = V16PodU128::new(
                 self.header
                     .pnl_matured_pos_tot
                     .get()
+                    .saturating_sub(matured_loss)
                     .min(self.header.pnl_pos_tot.get()),
             );
         }
@@ -8802,6 +10901,130 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(())
     }
 
+    fn admit_positive_pnl_increase(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        increase: u128,
+    ) -> V16Result<()> {
+        let admitted_h_eff = self.admitted_warmup_horizon(&account.as_view(), increase)?;
+        if admitted_h_eff == 0 {
+            self.header.pnl_matured_pos_tot = V16PodU128::new(
+                self.header
+                    .pnl_matured_pos_tot
+                    .get()
+                    .checked_add(increase)
+                    .ok_or(V16Error::ArithmeticOverflow)?,
+            );
+            return Ok(());
+        }
+        let reserve = append_warmup_reserve(
+            account.header.warmup_reserve.try_to_runtime()?,
+            increase,
+            admitted_h_eff,
+            self.header.current_slot.get(),
+        )?;
+        account.header.warmup_reserve = WarmupReserveV16Account::from_runtime(&reserve);
+        account.header.reserved_pnl = V16PodU128::new(
+            account
+                .header
+                .reserved_pnl
+                .get()
+                .checked_add(increase)
+                .ok_or(V16Error::ArithmeticOverflow)?,
+        );
+        Ok(())
+    }
+
+    /// Chooses the warmup horizon for fresh positive PnL. Outside an admission scope, and
+    /// outside live mode, fresh PnL is released immediately.
+    fn admitted_warmup_horizon(
+        &mut self,
+        account: &PortfolioV16View<'_>,
+        fresh_positive_pnl: u128,
+    ) -> V16Result<u64> {
+        let mut scope = match self.admission {
+            Some(scope) => scope,
+            None => return Ok(0),
+        };
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Ok(0);
+        }
+        let portfolio_account_id = account.header.provenance_header.portfolio_account_id;
+        let matured_plus_fresh = self
+            .header
+            .pnl_matured_pos_tot
+            .get()
+            .checked_add(fresh_positive_pnl)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        let use_h_max = scope.is_h_max_sticky(portfolio_account_id)
+            || self.h_lock_lane(
+                Some(account),
+                false,
+                #[cfg(feature = "fork-facade")]
+                None,
+            )? == HLockLaneV16::HMax
+            || matured_plus_fresh > self.residual();
+        if !use_h_max {
+            return Ok(scope.admission.admit_h_min);
+        }
+        scope.insert_h_max_sticky(portfolio_account_id)?;
+        self.admission = Some(scope);
+        Ok(scope.admission.admit_h_max)
+    }
+
+    fn advance_account_profit_warmup(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+    ) -> V16Result<u128> {
+        let (reserve, release) = advance_warmup_reserve(
+            account.header.warmup_reserve.try_to_runtime()?,
+            self.header.current_slot.get(),
+        )?;
+        account.header.warmup_reserve = WarmupReserveV16Account::from_runtime(&reserve);
+        if release == 0 {
+            return Ok(0);
+        }
+        account.header.reserved_pnl = V16PodU128::new(
+            account
+                .header
+                .reserved_pnl
+                .get()
+                .checked_sub(release)
+                .ok_or(V16Error::CounterUnderflow)?,
+        );
+        self.header.pnl_matured_pos_tot = V16PodU128::new(
+            self.header
+                .pnl_matured_pos_tot
+                .get()
+                .checked_add(release)
+                .ok_or(V16Error::ArithmeticOverflow)?,
+        );
+        account.header.health_cert.valid = 0;
+        Ok(release)
+    }
+
+    fn release_account_warmup_reserve(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+    ) -> V16Result<u128> {
+        let reserved = account.header.reserved_pnl.get();
+        if reserved == 0 {
+            return Ok(0);
+        }
+        self.header.pnl_matured_pos_tot = V16PodU128::new(
+            self.header
+                .pnl_matured_pos_tot
+                .get()
+                .checked_add(reserved)
+                .ok_or(V16Error::ArithmeticOverflow)?
+                .min(self.header.pnl_pos_tot.get()),
+        );
+        account.header.warmup_reserve = WarmupReserveV16Account::default();
+        account.header.reserved_pnl = V16PodU128::new(0);
+        account.header.health_cert.valid = 0;
+        Ok(reserved)
+    }
+
     fn face_claim_to_burn_for_support(
         &self,
         effective_support: u128,
@@ -8898,13 +11121,6 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         let new_pnl = retained_i128
             .checked_sub(remaining_i128)
             .ok_or(V16Error::ArithmeticOverflow)?;
-        account.header.reserved_pnl = V16PodU128::new(
-            account
-                .header
-                .reserved_pnl
-                .get()
-                .min(new_pnl.max(0) as u128),
-        );
         self.set_account_pnl(account, new_pnl)?;
         Ok(SupportLossApplicationV16 {
             support_consumed,
@@ -9125,7 +11341,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if asset_index >= self.header.config.max_market_slots.get() as usize
             || asset_index >= self.markets.len()
         {
-            return Err(V16Error::InvalidLeg);
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
         }
         let asset = self.markets[asset_index].engine.asset.try_to_runtime()?;
         Self::kf_target_for_leg_from_asset(asset, leg)
@@ -9169,7 +11387,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if asset_index >= self.header.config.max_market_slots.get() as usize
             || asset_index >= self.markets.len()
         {
-            return Err(V16Error::InvalidLeg);
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
         }
         let asset = self.markets[asset_index].engine.asset.try_to_runtime()?;
         Self::b_target_for_leg_from_asset(asset, leg)
@@ -9298,7 +11518,35 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         {
             return Err(V16Error::InvalidLeg);
         }
-        let (k_now, f_now, net) = Self::leg_kf_delta_for_settlement_from_asset(asset, leg)?;
+        let (k_now, f_now, mut net) = Self::leg_kf_delta_for_settlement_from_asset(asset, leg)?;
+        let mut isolated = account.header.isolated_margin[leg_slot].try_to_runtime()?;
+        // An isolated leg loses at most its allocation plus its settled gains; the excess is the
+        // leg's own deficit and never reaches the shared pool.
+        let mut isolated_excess_loss = 0u128;
+        if isolated.enabled && net < 0 {
+            let absorbable = i128::try_from(isolated.allocated_capital)
+                .map_err(|_| V16Error::ArithmeticOverflow)?
+                .checked_add(isolated.settled_pnl)
+                .ok_or(V16Error::ArithmeticOverflow)?
+                .max(0)
+                .unsigned_abs();
+            isolated_excess_loss = net.unsigned_abs().saturating_sub(absorbable);
+            net = net
+                .checked_add(
+                    i128::try_from(isolated_excess_loss)
+                        .map_err(|_| V16Error::ArithmeticOverflow)?,
+                )
+                .ok_or(V16Error::ArithmeticOverflow)?;
+        }
+        if isolated.enabled && net != 0 {
+            isolated.settled_pnl = isolated
+                .settled_pnl
+                .checked_add(net)
+                .ok_or(V16Error::ArithmeticOverflow)?;
+            validate_non_min_i128(isolated.settled_pnl)?;
+            account.header.isolated_margin[leg_slot] =
+                IsolatedMarginV16Account::from_runtime(&isolated);
+        }
         if net != 0 {
             if net > 0 {
                 let source_domain =
@@ -9317,6 +11565,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 )?;
             }
         }
+        if isolated_excess_loss != 0 {
+            self.cover_isolated_leg_excess_loss(asset_index, leg.side, isolated_excess_loss)?;
+        }
         leg.k_snap = k_now;
         leg.f_snap = f_now;
         account.header.legs[leg_slot] = PortfolioLegV16Account::from_runtime(&leg);
@@ -9324,6 +11575,68 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(())
     }
 
+    /// Covers an isolated leg's loss beyond its allocation from the opposite-side domain's
+    /// insurance, then socializes what is left to the opposite side through its B index.
+    fn cover_isolated_leg_excess_loss(
+        &mut self,
+        asset_index: usize,
+        side: SideV16,
+        excess_loss: u128,
+    ) -> V16Result<()> {
+        self.header.bankruptcy_hlock_active = 1;
+        let domain = self.insurance_domain_index(asset_index, opposite_side(side))?;
+        let used = excess_loss.min(self.available_domain_insurance(domain)?);
+        if used != 0 {
+            let vault_before = self.header.vault.get();
+            self.header.insurance = V16PodU128::new(
+                self.header
+                    .insurance
+                    .get()
+                    .checked_sub(used)
+                    .ok_or(V16Error::CounterUnderflow)?,
+            );
+            let (_, spent_before) = self.domain_insurance_budget_spent(domain)?;
+            self.set_domain_insurance_spent_core(
+                domain,
+                spent_before
+                    .checked_add(used)
+                    .ok_or(V16Error::ArithmeticOverflow)?,
+            )?;
+            TokenValueFlowProofV16::validate_insurance_to_close_insurance_spent(
+                used,
+                vault_before,
+                self.header.vault.get(),
+            )?;
+            self.emit(EngineEventV16::InsuranceSpent {
+                domain: Some(domain),
+                amount: used,
+            });
+        }
+        let mut remaining = excess_loss - used;
+        while remaining != 0 {
+            let outcome =
+                self.book_bankruptcy_residual_chunk_internal(asset_index, side, remaining)?;
+            let cleared = outcome
+                .booked_loss
+                .checked_add(outcome.explicit_loss)
+                .ok_or(V16Error::ArithmeticOverflow)?
+                .min(remaining);
+            if cleared == 0 {
+                return Err(self.reject(
+                    V16Error::RecoveryRequired
+                        .with_reason(V16ErrorReason::LiquidationRequiresRecovery),
+                ));
+            }
+            remaining -= cleared;
+            self.emit(EngineEventV16::ResidualBooked {
+                asset_index,
+                bankrupt_side: side,
+                outcome,
+            });
+        }
+        Ok(())
+    }
+
     fn clear_account_stale(&mut self, account: &mut PortfolioV16ViewMut<'_>) -> V16Result<()> {
         if decode_bool(account.header.stale_state)? {
             account.header.stale_state = 0;
@@ -9348,6 +11661,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         let mut initial_req = 0u128;
         let mut maintenance_req = 0u128;
         let mut worst_case_loss = 0u128;
+        let mut gross_notional = 0u128;
+        let mut margin_legs = [PortfolioMarginLegV16::EMPTY; V16_MAX_PORTFOLIO_ASSETS_N];
         let mut slot = 0usize;
         while slot < V16_MAX_PORTFOLIO_ASSETS_N {
             let leg = account.header.legs[slot].try_to_runtime()?;
@@ -9381,10 +11696,21 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             )?;
             let (leg_initial, leg_maintenance, leg_worst_case_loss) =
                 V16Core::health_requirements_from_notional_and_target_lag(
-                    config,
+                    self.asset_risk_config(config, asset_index)?,
                     risk_notional,
                     target_lag_penalty,
                 )?;
+            // An isolated leg locks its whole allocation and reports its own shortfall, so
+            // neither its surplus nor its deficit can be netted against the shared pool.
+            let isolated = account.header.isolated_margin[slot].try_to_runtime()?;
+            let (leg_initial, leg_maintenance) = if isolated.enabled {
+                (
+                    leg_initial.max(isolated.equity()),
+                    leg_maintenance.max(isolated.equity()),
+                )
+            } else {
+                (leg_initial, leg_maintenance)
+            };
             initial_req = initial_req
                 .checked_add(leg_initial)
                 .ok_or(V16Error::ArithmeticOverflow)?;
@@ -9394,8 +11720,29 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             worst_case_loss = worst_case_loss
                 .checked_add(leg_worst_case_loss)
                 .ok_or(V16Error::ArithmeticOverflow)?;
+            if isolated.enabled {
+                slot += 1;
+                continue;
+            }
+            gross_notional = gross_notional
+                .checked_add(risk_notional)
+                .ok_or(V16Error::ArithmeticOverflow)?;
+            margin_legs[slot] = PortfolioMarginLegV16 {
+                active: true,
+                asset_index,
+                side: leg.side,
+                base_initial: leg_initial - target_lag_penalty,
+                base_maintenance: leg_maintenance - target_lag_penalty,
+            };
             slot += 1;
         }
+        let (initial_credit, maintenance_credit) =
+            self.portfolio_margin_offset_credit(&margin_legs)?;
+        let initial_req = (initial_req - initial_credit).max(checked_fee_bps(
+            gross_notional,
+            account.header.account_initial_margin_bps.get(),
+        )?);
+        let maintenance_req = maintenance_req - maintenance_credit;
         let equity = self.account_haircut_equity(account)?;
         Ok(HealthCertV16 {
             certified_equity: equity,
@@ -9458,6 +11805,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         let active_leg_cap = config.max_portfolio_assets as usize;
         let configured_assets = config.max_market_slots as usize;
         let bitmap = account.header.active_bitmap.map(V16PodU64::get);
+        let mut gross_notional = 0u128;
+        let mut margin_legs = [PortfolioMarginLegV16::EMPTY; V16_MAX_PORTFOLIO_ASSETS_N];
         let mut seen_assets = [u32::MAX; V16_MAX_PORTFOLIO_ASSETS_N];
         let mut seen_asset_count = 0usize;
         let mut slot = 0usize;
@@ -9554,10 +11903,21 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             )?;
             let (leg_initial, leg_maintenance, leg_worst_case_loss) =
                 V16Core::health_requirements_from_notional_and_target_lag(
-                    config,
+                    self.asset_risk_config(config, asset_index)?,
                     risk_notional,
                     target_lag_penalty,
                 )?;
+            // An isolated leg locks its whole allocation and reports its own shortfall, so
+            // neither its surplus nor its deficit can be netted against the shared pool.
+            let isolated = account.header.isolated_margin[slot].try_to_runtime()?;
+            let (leg_initial, leg_maintenance) = if isolated.enabled {
+                (
+                    leg_initial.max(isolated.equity()),
+                    leg_maintenance.max(isolated.equity()),
+                )
+            } else {
+                (leg_initial, leg_maintenance)
+            };
             initial_req = initial_req
                 .checked_add(leg_initial)
                 .ok_or(V16Error::ArithmeticOverflow)?;
@@ -9567,8 +11927,29 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             worst_case_loss = worst_case_loss
                 .checked_add(leg_worst_case_loss)
                 .ok_or(V16Error::ArithmeticOverflow)?;
+            if isolated.enabled {
+                slot += 1;
+                continue;
+            }
+            gross_notional = gross_notional
+                .checked_add(risk_notional)
+                .ok_or(V16Error::ArithmeticOverflow)?;
+            margin_legs[slot] = PortfolioMarginLegV16 {
+                active: true,
+                asset_index,
+                side: refreshed.side,
+                base_initial: leg_initial - target_lag_penalty,
+                base_maintenance: leg_maintenance - target_lag_penalty,
+            };
             slot += 1;
         }
+        let (initial_credit, maintenance_credit) =
+            self.portfolio_margin_offset_credit(&margin_legs)?;
+        let initial_req = (initial_req - initial_credit).max(checked_fee_bps(
+            gross_notional,
+            account.header.account_initial_margin_bps.get(),
+        )?);
+        let maintenance_req = maintenance_req - maintenance_credit;
         self.settle_negative_pnl_from_principal_core_not_atomic(account)?;
         self.collect_account_backing_utilization_fees_not_atomic(account)?;
         if decode_bool(account.header.b_stale_state)? {
@@ -9595,12 +11976,34 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             active_bitmap_at_cert: account.header.active_bitmap.map(V16PodU64::get),
             valid: true,
         };
-        account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+        self.store_health_cert(account, &cert)?;
         self.validate_account_audit_scan(&account.as_view())?;
         self.validate_shape_audit_scan()?;
         Ok(AccountRefreshCertOutcomeV16::Certified(cert))
     }
 
+    /// Writes `cert` and keeps `liquidatable_since_slot` in step with it: stamped with the
+    /// current slot by the first certificate showing the account liquidatable, cleared by one
+    /// showing it healthy.
+    fn store_health_cert(
+        &self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        cert: &HealthCertV16,
+    ) -> V16Result<()> {
+        account.header.health_cert = HealthCertV16Account::from_runtime(cert);
+        let liquidatable = cert.certified_liq_deficit != 0
+            || (Self::account_has_isolated_margin(&account.as_view())?
+                && self.isolated_margin_deficit(&account.as_view())? != 0);
+        if !liquidatable {
+            account.header.liquidatable_since_slot = V16PodU64::new(0);
+        } else if account.header.liquidatable_since_slot.get() == 0 {
+            // Zero marks a healthy account, so a sighting at slot zero is stamped as slot one.
+            account.header.liquidatable_since_slot =
+                V16PodU64::new(self.header.current_slot.get().max(1));
+        }
+        Ok(())
+    }
+
     fn has_b_stale_leg(account: &PortfolioV16View<'_>) -> V16Result<bool> {
         let mut slot = 0usize;
         while slot < V16_MAX_PORTFOLIO_ASSETS_N {
@@ -9920,19 +12323,31 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             true,
             price_override,
         )?;
-        account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+        self.store_health_cert(account, &cert)?;
         Ok(cert)
     }
 
     fn require_asset_accruable(&self, asset_index: usize) -> V16Result<()> {
-        match self.asset_state(asset_index)?.lifecycle {
+        let asset = self.asset_state(asset_index)?;
+        if asset.resolved_slot != 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetResolved))
+            );
+        }
+        match asset.lifecycle {
             AssetLifecycleV16::Active | AssetLifecycleV16::DrainOnly => Ok(()),
             _ => Err(V16Error::LockActive),
         }
     }
 
     fn require_asset_mark_pushable(&self, asset_index: usize) -> V16Result<()> {
-        match self.asset_state(asset_index)?.lifecycle {
+        let asset = self.asset_state(asset_index)?;
+        if asset.resolved_slot != 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetResolved))
+            );
+        }
+        match asset.lifecycle {
             AssetLifecycleV16::Active | AssetLifecycleV16::DrainOnly => Ok(()),
             _ => Err(V16Error::LockActive),
         }
@@ -10008,6 +12423,89 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.validate_shape()
     }
 
+    /// Funding rate the next accrual segment of `asset_index` would apply under the engine
+    /// funding model. Errors while the model is disabled (`funding_twap_window_slots == 0`).
+    pub fn engine_funding_rate_e9(&self, asset_index: usize) -> V16Result<i128> {
+        self.validate_configured_asset_index(asset_index)?;
+        let config =
+            self.asset_risk_config(self.header.config.try_to_runtime_shape()?, asset_index)?;
+        V16Core::premium_index_funding_rate_e9(config, self.asset_state(asset_index)?)
+    }
+
+    /// Next effective price on the capped staircase from the asset's `P_last` toward its stored
+    /// raw oracle target, for the accrual segment that `accrue_asset_to_not_atomic(now_slot)`
+    /// would apply. Same-slot calls return `P_last`; unexposed assets move straight to the target.
+    /// An exposed asset whose cap rounds to a zero step cannot catch up and needs recovery.
+    pub fn next_oracle_catchup_price(&self, asset_index: usize, now_slot: u64) -> V16Result<u64> {
+        self.validate_configured_asset_index(asset_index)?;
+        let config =
+            self.asset_risk_config(self.header.config.try_to_runtime_shape()?, asset_index)?;
+        let asset = self.asset_state(asset_index)?;
+        if now_slot < asset.slot_last {
+            return Err(
+                self.reject(V16Error::InvalidConfig.with_reason(V16ErrorReason::SlotBehindMarket))
+            );
+        }
+        let target = asset.raw_oracle_target_price;
+        let last = asset.effective_price;
+        let segment_dt = (now_slot - asset.slot_last).min(config.max_accrual_dt_slots);
+        if target == last || segment_dt == 0 {
+            return Ok(last);
+        }
+        if asset.oi_eff_long_q == 0 && asset.oi_eff_short_q == 0 {
+            return Ok(target);
+        }
+        let max_delta = wide_mul_div_floor_u128(
+            last as u128,
+            (config.max_price_move_bps_per_slot as u128) * (segment_dt as u128),
+            MAX_MARGIN_BPS as u128,
+        );
+        if max_delta == 0 {
+            return Err(self.reject(
+                V16Error::RecoveryRequired.with_reason(V16ErrorReason::OracleCatchupStalled),
+            ));
+        }
+        let step = max_delta.min(target.abs_diff(last) as u128) as u64;
+        Ok(if target > last {
+            last + step
+        } else {
+            last - step
+        })
+    }
+
+    /// Accrues the asset toward `now_slot` along the capped staircase of
+    /// `next_oracle_catchup_price`, one `max_accrual_dt_slots` segment per step, for at most
+    /// `max_segments` steps. `caught_up` reports whether the effective price reached the raw
+    /// target; `slot_last < now_slot` means more segments remain.
+    pub fn catch_up_asset_oracle_not_atomic(
+        &mut self,
+        asset_index: usize,
+        now_slot: u64,
+        funding_rate_e9: i128,
+        max_segments: u32,
+        protective_progress_committed: bool,
+    ) -> V16Result<OracleCatchupOutcomeV16> {
+        let mut segments = 0u32;
+        while segments < max_segments && self.asset_state(asset_index)?.slot_last < now_slot {
+            let effective_price = self.next_oracle_catchup_price(asset_index, now_slot)?;
+            self.accrue_asset_to_not_atomic(
+                asset_index,
+                now_slot,
+                effective_price,
+                funding_rate_e9,
+                protective_progress_committed,
+            )?;
+            segments += 1;
+        }
+        let asset = self.asset_state(asset_index)?;
+        Ok(OracleCatchupOutcomeV16 {
+            segments,
+            effective_price: asset.effective_price,
+            slot_last: asset.slot_last,
+            caught_up: asset.effective_price == asset.raw_oracle_target_price,
+        })
+    }
+
     pub fn reset_empty_asset_oracle_anchor_not_atomic(
         &mut self,
         asset_index: usize,
@@ -10077,21 +12575,32 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     ) -> V16Result<AccrueAssetOutcomeV16> {
         let config = self.header.config.try_to_runtime_shape()?;
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         if asset_index >= config.max_market_slots as usize
             || asset_index >= self.markets.len()
             || effective_price == 0
             || effective_price > MAX_ORACLE_PRICE
-            || funding_rate_e9.unsigned_abs() > config.max_abs_funding_e9_per_slot as u128
             || now_slot < self.header.current_slot.get()
         {
-            return Err(V16Error::InvalidConfig);
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::AccrualRequestOutOfBounds),
+            ));
+        }
+        let config = self.asset_risk_config(config, asset_index)?;
+        if funding_rate_e9.unsigned_abs() > config.max_abs_funding_e9_per_slot as u128 {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::AccrualFundingRateOutOfBounds),
+            ));
         }
         self.require_asset_accruable(asset_index)?;
         let old = self.asset_state(asset_index)?;
         if now_slot < old.slot_last {
-            return Err(V16Error::InvalidConfig);
+            return Err(
+                self.reject(V16Error::InvalidConfig.with_reason(V16ErrorReason::SlotBehindMarket))
+            );
         }
         let dt_total = now_slot - old.slot_last;
         let segment_dt = if dt_total > config.max_accrual_dt_slots {
@@ -10099,6 +12608,19 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         } else {
             dt_total
         };
+        // With the engine funding model enabled the segment accrues at the rate derived from
+        // the TWAPs up to its start, and the caller must not supply a rate of its own.
+        let funding_rate_e9 = if config.funding_twap_window_slots != 0 {
+            if funding_rate_e9 != 0 {
+                return Err(self.reject(
+                    V16Error::InvalidConfig
+                        .with_reason(V16ErrorReason::AccrualFundingRateNotEngineDerived),
+                ));
+            }
+            V16Core::premium_index_funding_rate_e9(config, old)?
+        } else {
+            funding_rate_e9
+        };
         let activity = V16Core::accrual_activity_for_asset_segment(
             old,
             segment_dt,
@@ -10138,19 +12660,35 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         let a_short_i = i128::try_from(old.a_short).map_err(|_| V16Error::ArithmeticOverflow)?;
         let k_delta_long = checked_i128_mul(price_delta, a_long_i)?;
         let k_delta_short = checked_i128_mul(price_delta, a_short_i)?;
-        // Funding: floor at FUNDING_DEN ONCE (identical to before), THEN scale per side by A_side.
-        let (funding_delta_long, funding_delta_short) = if activity.funding_active {
-            let n = funding_rate_e9
-                .checked_mul(segment_dt as i128)
-                .and_then(|v| v.checked_mul(effective_price as i128))
+        // Funding: floor at FUNDING_DEN per side, THEN scale per side by A_side. The paying side
+        // is charged the spread on top of the rate and the floor rounds toward it, so the
+        // receiving side never realizes more than the paying side pays. Without a spread both
+        // sides share one numerator, exactly as before.
+        let (funding_delta_long, funding_delta_short, spread_atoms) = if activity.funding_active {
+            let spread = config.funding_spread_e9_per_slot as i128;
+            let (rate_long, rate_short, paying_oi_q) = if funding_rate_e9 > 0 {
+                (funding_rate_e9 + spread, funding_rate_e9, old.oi_eff_long_q)
+            } else {
+                (
+                    funding_rate_e9,
+                    funding_rate_e9 - spread,
+                    old.oi_eff_short_q,
+                )
+            };
+            let fund_num_long =
+                V16Core::funding_num_for_segment(rate_long, segment_dt, effective_price)?;
+            let fund_num_short =
+                V16Core::funding_num_for_segment(rate_short, segment_dt, effective_price)?;
+            let spread_num = fund_num_long
+                .checked_sub(fund_num_short)
                 .ok_or(V16Error::ArithmeticOverflow)?;
-            let fund_num_total = floor_div_signed_conservative_i128(n, FUNDING_DEN);
             (
-                checked_i128_mul(fund_num_total, a_long_i)?,
-                checked_i128_mul(fund_num_total, a_short_i)?,
+                checked_i128_mul(fund_num_long, a_long_i)?,
+                checked_i128_mul(fund_num_short, a_short_i)?,
+                wide_mul_div_floor_u128(spread_num.unsigned_abs(), paying_oi_q, POS_SCALE),
             )
         } else {
-            (0, 0)
+            (0, 0, 0)
         };
 
         let mut asset = old;
@@ -10160,10 +12698,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         asset.f_short_num = add_non_min_i128(asset.f_short_num, funding_delta_short)?;
         asset.effective_price = effective_price;
         asset.fund_px_last = effective_price;
+        V16Core::roll_funding_twap(&mut asset, config, segment_dt, effective_price)?;
         asset.slot_last = asset
             .slot_last
             .checked_add(segment_dt)
             .ok_or(V16Error::ArithmeticOverflow)?;
+        asset.funding_spread_unswept = asset
+            .funding_spread_unswept
+            .checked_add(spread_atoms)
+            .ok_or(V16Error::ArithmeticOverflow)?;
         self.set_asset_state(asset_index, asset)?;
         self.header.current_slot = V16PodU64::new(now_slot);
         // Hot paths are asset-local: scanning all markets here makes every
@@ -10180,6 +12723,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                     .checked_add(1)
                     .ok_or(V16Error::CounterOverflow)?,
             );
+            // Spec 5.3: each exposed price move consumes
+            // floor(|dP| * 10_000 * SCALE / P_last) of the stress envelope, so the HMax lift
+            // does not depend on the wrapper feeding the accumulator itself.
+            let consumption_bps_e9 = wide_mul_div_floor_u128(
+                (effective_price.abs_diff(old.effective_price) as u128) * MAX_MARGIN_BPS as u128,
+                STRESS_CONSUMPTION_SCALE,
+                old.effective_price as u128,
+            );
+            self.apply_stress_envelope_progress(consumption_bps_e9, now_slot)?;
         }
         if activity.funding_active {
             self.header.funding_epoch = V16PodU64::new(
@@ -10191,6 +12743,14 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             );
         }
         self.validate_shape_audit_scan()?;
+        self.emit(EngineEventV16::Accrual {
+            asset_index,
+            dt: segment_dt,
+            k_long_delta: k_delta_long,
+            k_short_delta: -k_delta_short,
+            f_long_num_delta: -funding_delta_long,
+            f_short_num_delta: funding_delta_short,
+        });
         Ok(AccrueAssetOutcomeV16 {
             dt: segment_dt,
             price_move_active: activity.price_move_active,
@@ -10200,15 +12760,79 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         })
     }
 
-    fn declare_permissionless_recovery(
+    /// Moves funding spread accrued on `asset_index` into insurance. The spread only reaches the
+    /// vault residual once the paying side's losses settle and the counterparty backing they
+    /// fund is released, so each sweep is bounded by the residual left above every junior claim
+    /// and the rest stays pending for a later sweep. Spread matched by a socialized loss on the
+    /// asset is forfeited when that loss is booked.
+    pub fn sweep_funding_spread_to_insurance_not_atomic(
         &mut self,
-        reason: PermissionlessRecoveryReasonV16,
-    ) -> V16Result<PermissionlessProgressOutcomeV16> {
-        if !decode_bool(self.header.config.permissionless_recovery_enabled)? {
-            return Err(V16Error::InvalidConfig);
-        }
-        if decode_market_mode(self.header.mode)? == MarketModeV16::Resolved {
-            return Err(V16Error::LockActive);
+        asset_index: usize,
+    ) -> V16Result<u128> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        self.validate_configured_asset_index(asset_index)?;
+        let mut asset = self.asset_state(asset_index)?;
+        let (swept, next_unswept, next_insurance) = Self::funding_spread_sweep_delta(
+            asset.funding_spread_unswept,
+            self.residual(),
+            self.junior_claim_bound(),
+            self.header.insurance.get(),
+        )?;
+        if swept == 0 {
+            return Ok(0);
+        }
+        let vault = self.header.vault.get();
+        asset.funding_spread_unswept = next_unswept;
+        self.header.insurance = V16PodU128::new(next_insurance);
+        TokenValueFlowProofV16::protocol_surplus_to_insurance_capital(swept, vault, vault)?
+            .validate()?;
+        self.set_asset_state(asset_index, asset)?;
+        self.validate_shape()?;
+        Ok(swept)
+    }
+
+    /// Moves unswept funding spread into insurance, capped by the residual left over after every
+    /// junior claim, so the sweep never reaches into value owed to positive PnL.
+    fn funding_spread_sweep_delta(
+        unswept: u128,
+        residual: u128,
+        junior_claim_bound: u128,
+        insurance: u128,
+    ) -> V16Result<(u128, u128, u128)> {
+        let swept = unswept.min(residual.saturating_sub(junior_claim_bound));
+        let next_insurance = insurance
+            .checked_add(swept)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        Ok((swept, unswept - swept, next_insurance))
+    }
+
+    #[cfg(kani)]
+    pub fn kani_funding_spread_sweep_delta(
+        unswept: u128,
+        residual: u128,
+        junior_claim_bound: u128,
+        insurance: u128,
+    ) -> V16Result<(u128, u128, u128)> {
+        Self::funding_spread_sweep_delta(unswept, residual, junior_claim_bound, insurance)
+    }
+
+    fn declare_permissionless_recovery(
+        &mut self,
+        reason: PermissionlessRecoveryReasonV16,
+    ) -> V16Result<PermissionlessProgressOutcomeV16> {
+        if !decode_bool(self.header.config.permissionless_recovery_enabled)? {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::PermissionlessRecoveryDisabled),
+            ));
+        }
+        if decode_market_mode(self.header.mode)? == MarketModeV16::Resolved {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketResolved))
+            );
         }
         if let Some(existing_reason) = self.header.recovery_reason.try_to_runtime()? {
             return Ok(PermissionlessProgressOutcomeV16::RecoveryDeclared(
@@ -10226,6 +12850,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         account: &mut PortfolioV16ViewMut<'_>,
         request: PermissionlessCrankRequestV16,
     ) -> V16Result<PermissionlessProgressOutcomeV16> {
+        if self.admission.is_none() {
+            let admission = self.default_warmup_admission();
+            return self
+                .permissionless_crank_with_admission_not_atomic(account, request, admission);
+        }
         self.validate_unconfigured_market_tail()?;
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live
             && !matches!(request.action, PermissionlessCrankActionV16::Recover(_))
@@ -10263,7 +12892,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             PermissionlessCrankActionV16::Liquidate(_) => {
                 if let PermissionlessCrankActionV16::Liquidate(liq) = request.action {
                     let liquidated_asset_index = liq.asset_index;
-                    self.liquidate_account_not_atomic(account, liq)?;
+                    self.liquidate_account_sized_not_atomic(account, liq, true)?;
                     liquidated_asset_index == request.asset_index
                 } else {
                     unreachable!()
@@ -10283,6 +12912,265 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(PermissionlessProgressOutcomeV16::AccountCurrent)
     }
 
+    /// Keeper crank over `request.asset_index`. The asset is accrued once up front; Phase 1 then
+    /// refreshes `candidates` in order until `max_revalidations` is spent or a liquidation
+    /// schedules a side reset, liquidating each candidate whose requested leg the fresh
+    /// certificate shows liquidatable. Phase 2 always runs and touches `sweep`, which must hold
+    /// the portfolios materialized at `[rr_cursor_position, rr_cursor_position + advance)` in
+    /// order. A requested liquidation of a healthy leg, or one blocked by a pending loss barrier,
+    /// is counted in `liquidations_skipped`; any other rejection aborts the crank. No account
+    /// may appear twice.
+    pub fn keeper_crank_not_atomic(
+        &mut self,
+        request: KeeperCrankRequestV16,
+        candidates: &mut [KeeperCandidateV16<'_>],
+        sweep: &mut [PortfolioV16ViewMut<'_>],
+    ) -> V16Result<KeeperCrankOutcomeV16> {
+        self.validate_unconfigured_market_tail()?;
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        let sweep_limit = self.header.materialized_portfolio_count.get();
+        let mut rr_cursor_position = self.header.rr_cursor_position.get();
+        let mut sweep_generation = self.header.sweep_generation.get();
+        if rr_cursor_position >= sweep_limit && rr_cursor_position != 0 {
+            rr_cursor_position = 0;
+            sweep_generation = sweep_generation
+                .checked_add(1)
+                .ok_or(V16Error::CounterOverflow)?;
+        }
+        let rr_advance = request.rr_window_size.min(sweep_limit - rr_cursor_position);
+        if sweep.len() as u64 != rr_advance {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::KeeperSweepWindowMismatch),
+            ));
+        }
+        self.require_distinct_keeper_accounts(candidates, sweep)?;
+        let mut j = 0usize;
+        while j < sweep.len() {
+            sweep[j].validate_with_market(&self.as_view())?;
+            if !decode_bool(sweep[j].header.materialized)?
+                || sweep[j].header.materialized_index.get() != rr_cursor_position + j as u64
+            {
+                return Err(self.reject(
+                    V16Error::InvalidConfig
+                        .with_reason(V16ErrorReason::KeeperSweepAccountOutOfOrder),
+                ));
+            }
+            j += 1;
+        }
+        // The accrual is protective only through accounts this call is certain to touch: every
+        // sweep account and the first live candidate, each validated above or here.
+        let mut protective_progress = false;
+        let mut j = 0usize;
+        while j < sweep.len() {
+            protective_progress |= self.keeper_account_touches_asset(&sweep[j], request)?;
+            j += 1;
+        }
+        if request.max_revalidations != 0 {
+            let mut i = 0usize;
+            while i < candidates.len() {
+                if !candidates[i]
+                    .account
+                    .as_view()
+                    .is_empty_for_dematerialization()?
+                {
+                    candidates[i]
+                        .account
+                        .validate_with_market(&self.as_view())?;
+                    protective_progress |=
+                        self.keeper_account_touches_asset(&candidates[i].account, request)?;
+                    break;
+                }
+                i += 1;
+            }
+        }
+        self.accrue_asset_to_not_atomic(
+            request.asset_index,
+            request.now_slot,
+            request.effective_price,
+            request.funding_rate_e9,
+            protective_progress,
+        )?;
+        self.with_warmup_admission(request.admission, |market| {
+            let mut outcome = KeeperCrankOutcomeV16 {
+                revalidated: 0,
+                liquidated: 0,
+                liquidations_skipped: 0,
+                stopped_on_pending_reset: false,
+                swept: 0,
+                rr_cursor_position: 0,
+                sweep_generation: 0,
+            };
+            let mut i = 0usize;
+            while i < candidates.len() && outcome.revalidated < request.max_revalidations {
+                let candidate = &mut candidates[i];
+                i += 1;
+                if candidate
+                    .account
+                    .as_view()
+                    .is_empty_for_dematerialization()?
+                {
+                    continue;
+                }
+                let certified = market.keeper_touch_account(&mut candidate.account, request)?;
+                outcome.revalidated += 1;
+                let liq = match (certified, candidate.liquidation) {
+                    (Some(_), Some(liq)) => liq,
+                    _ => continue,
+                };
+                if !market.keeper_liquidate_or_skip(&mut candidate.account, liq)? {
+                    outcome.liquidations_skipped += 1;
+                    continue;
+                }
+                outcome.liquidated += 1;
+                let asset = market.asset_state(liq.asset_index)?;
+                if asset.mode_long == SideModeV16::ResetPending
+                    || asset.mode_short == SideModeV16::ResetPending
+                {
+                    outcome.stopped_on_pending_reset = true;
+                    break;
+                }
+            }
+
+            let mut j = 0usize;
+            while j < sweep.len() {
+                if !sweep[j].as_view().is_empty_for_dematerialization()? {
+                    market.keeper_touch_account(&mut sweep[j], request)?;
+                }
+                j += 1;
+            }
+            outcome.swept = rr_advance;
+            rr_cursor_position += rr_advance;
+            if rr_cursor_position == sweep_limit && sweep_limit != 0 {
+                rr_cursor_position = 0;
+                sweep_generation = sweep_generation
+                    .checked_add(1)
+                    .ok_or(V16Error::CounterOverflow)?;
+            }
+            market.header.rr_cursor_position = V16PodU64::new(rr_cursor_position);
+            market.header.sweep_generation = V16PodU64::new(sweep_generation);
+            outcome.rr_cursor_position = rr_cursor_position;
+            outcome.sweep_generation = sweep_generation;
+            market.validate_shape()?;
+            Ok(outcome)
+        })
+    }
+
+    fn require_distinct_keeper_accounts(
+        &self,
+        candidates: &[KeeperCandidateV16<'_>],
+        sweep: &[PortfolioV16ViewMut<'_>],
+    ) -> V16Result<()> {
+        let id = |k: usize| {
+            if k < candidates.len() {
+                candidates[k]
+                    .account
+                    .header
+                    .provenance_header
+                    .portfolio_account_id
+            } else {
+                sweep[k - candidates.len()]
+                    .header
+                    .provenance_header
+                    .portfolio_account_id
+            }
+        };
+        let total = candidates.len() + sweep.len();
+        let mut a = 0usize;
+        while a < total {
+            let mut b = a + 1;
+            while b < total {
+                if id(a) == id(b) {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig.with_reason(V16ErrorReason::KeeperAccountRepeated),
+                    ));
+                }
+                b += 1;
+            }
+            a += 1;
+        }
+        Ok(())
+    }
+
+    /// Attempts one keeper liquidation against the candidate's fresh certificate. A leg that is
+    /// not liquidatable, or whose close would touch a pending loss barrier, is rejected before
+    /// anything is charged or emitted and returns `false`; every other error aborts the crank.
+    fn keeper_liquidate_or_skip(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        liq: LiquidationRequestV16,
+    ) -> V16Result<bool> {
+        let leg_slot =
+            Self::require_active_leg_slot_for_asset(&account.as_view(), liq.asset_index)?;
+        let attempt = match self
+            .call_with_reason(|m| m.require_leg_liquidatable(&account.as_view(), leg_slot))
+        {
+            Ok(()) => {
+                self.call_with_reason(|m| m.liquidate_account_not_atomic(account, liq).map(|_| ()))
+            }
+            Err(rejection) => Err(rejection),
+        };
+        match attempt {
+            Ok(()) => Ok(true),
+            Err(rejection) if Self::keeper_skips_liquidation(rejection) => Ok(false),
+            Err(rejection) => Err(self.reject(rejection)),
+        }
+    }
+
+    fn keeper_skips_liquidation(rejection: V16Rejection) -> bool {
+        matches!(
+            (rejection.error, rejection.reason),
+            (
+                V16Error::NonProgress,
+                V16ErrorReason::LiquidationAccountHealthy
+                    | V16ErrorReason::LiquidationIsolatedMarginHealthy
+                    | V16ErrorReason::LiquidationCrossMarginHealthy
+            ) | (
+                V16Error::LockActive,
+                V16ErrorReason::LiquidationTouchesPendingLossBarrier
+            )
+        )
+    }
+
+    fn keeper_account_touches_asset(
+        &self,
+        account: &PortfolioV16ViewMut<'_>,
+        request: KeeperCrankRequestV16,
+    ) -> V16Result<bool> {
+        Ok(
+            request.asset_index < self.header.config.max_market_slots.get() as usize
+                && Self::active_leg_slot_for_asset(&account.as_view(), request.asset_index)?
+                    .is_some(),
+        )
+    }
+
+    /// Advances warmup and refreshes one keeper-touched account. Returns the fresh
+    /// certificate, or `None` when only a B chunk was settled.
+    fn keeper_touch_account(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        request: KeeperCrankRequestV16,
+    ) -> V16Result<Option<HealthCertV16>> {
+        // Each keeper-touched account is its own admission context, so the h_max sticky set
+        // only has to span this account's touches.
+        if let Some(scope) = self.admission.as_mut() {
+            scope.h_max_sticky_accounts = [None; 2];
+        }
+        self.touch_account_warmup_for_admission(account, request.admission)?;
+        match self.refresh_account_and_certify_not_atomic(
+            account,
+            None,
+            self.header.config.public_b_chunk_atoms.get(),
+            true,
+        )? {
+            AccountRefreshCertOutcomeV16::Certified(cert) => Ok(Some(cert)),
+            AccountRefreshCertOutcomeV16::BChunk(_) => Ok(None),
+        }
+    }
+
     fn active_leg_slot_for_asset(
         account: &PortfolioV16View<'_>,
         asset_index: usize,
@@ -10346,17 +13234,258 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if asset_index >= self.header.config.max_market_slots.get() as usize
             || asset_index >= self.markets.len()
         {
-            return Err(V16Error::InvalidLeg);
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
         }
         self.markets[asset_index].engine.asset.try_to_runtime()
     }
 
-    fn set_asset_state(&mut self, asset_index: usize, asset: AssetStateV16) -> V16Result<()> {
+    fn asset_risk_config(&self, config: V16Config, asset_index: usize) -> V16Result<V16Config> {
+        self.asset_risk_config_after(config, None, asset_index)
+    }
+
+    /// `asset_risk_config` with `profile_update` installed in place of the stored profile.
+    fn asset_risk_config_after(
+        &self,
+        config: V16Config,
+        profile_update: Option<AssetRiskProfileUpdateV16>,
+        asset_index: usize,
+    ) -> V16Result<V16Config> {
         if asset_index >= self.header.config.max_market_slots.get() as usize
             || asset_index >= self.markets.len()
         {
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
+        }
+        let profile = match profile_update {
+            Some(update) if update.asset_index as usize == asset_index => update.profile,
+            _ => self.markets[asset_index]
+                .engine
+                .risk_profile
+                .try_to_runtime()?,
+        };
+        Ok(config.with_asset_risk_profile(profile))
+    }
+
+    fn account_has_isolated_margin(account: &PortfolioV16View<'_>) -> V16Result<bool> {
+        let mut slot = 0usize;
+        while slot < V16_MAX_PORTFOLIO_ASSETS_N {
+            if account.header.isolated_margin[slot]
+                .try_to_runtime()?
+                .enabled
+            {
+                return Ok(true);
+            }
+            slot += 1;
+        }
+        Ok(false)
+    }
+
+    /// Initial and maintenance requirement of one active leg at the asset's effective price,
+    /// before any portfolio-margin credit.
+    fn leg_margin_requirements(
+        &self,
+        account: &PortfolioV16View<'_>,
+        leg_slot: usize,
+    ) -> V16Result<(u128, u128)> {
+        let config = self.header.config.try_to_runtime_shape()?;
+        let leg = account.header.legs[leg_slot].try_to_runtime()?;
+        if !leg.active {
             return Err(V16Error::InvalidLeg);
         }
+        let asset_index = leg.asset_index as usize;
+        let asset = self.asset_state(asset_index)?;
+        let target_lag_penalty = V16Core::target_effective_lag_loss_penalty(
+            leg.basis_pos_q.unsigned_abs(),
+            leg.side,
+            asset.effective_price,
+            asset.raw_oracle_target_price,
+        )?;
+        let (leg_initial, leg_maintenance, _) =
+            V16Core::health_requirements_from_notional_and_target_lag(
+                self.asset_risk_config(config, asset_index)?,
+                risk_notional_ceil(leg.basis_pos_q.unsigned_abs(), asset.effective_price)?,
+                target_lag_penalty,
+            )?;
+        Ok((leg_initial, leg_maintenance))
+    }
+
+    /// Sum of isolated-leg maintenance shortfalls. The account's certified deficit above this
+    /// amount belongs to the shared cross-margin pool.
+    fn isolated_margin_deficit(&self, account: &PortfolioV16View<'_>) -> V16Result<u128> {
+        let mut deficit = 0u128;
+        let mut slot = 0usize;
+        while slot < V16_MAX_PORTFOLIO_ASSETS_N {
+            let isolated = account.header.isolated_margin[slot].try_to_runtime()?;
+            if isolated.enabled {
+                let (_, leg_maintenance) = self.leg_margin_requirements(account, slot)?;
+                deficit = deficit
+                    .checked_add(leg_maintenance.saturating_sub(isolated.equity()))
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+            }
+            slot += 1;
+        }
+        Ok(deficit)
+    }
+
+    fn portfolio_margin_offsets_configured(&self) -> V16Result<bool> {
+        let mut i = 0usize;
+        while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            if self.header.portfolio_margin_offsets[i]
+                .try_to_runtime()?
+                .offset_bps
+                != 0
+            {
+                return Ok(true);
+            }
+            i += 1;
+        }
+        Ok(false)
+    }
+
+    /// Requirement credit from the offset table for a portfolio's per-leg base requirements.
+    /// Each entry pairs an opposite-side leg on `asset_a` with one on `asset_b`; the smaller
+    /// remaining base requirement is the matched amount, `offset_bps` of it is credited on both
+    /// legs, and the matched amount is consumed so a leg is never offset twice.
+    fn portfolio_margin_offset_credit(
+        &self,
+        legs: &[PortfolioMarginLegV16; V16_MAX_PORTFOLIO_ASSETS_N],
+    ) -> V16Result<(u128, u128)> {
+        let mut remaining_initial = [0u128; V16_MAX_PORTFOLIO_ASSETS_N];
+        let mut remaining_maintenance = [0u128; V16_MAX_PORTFOLIO_ASSETS_N];
+        let mut slot = 0usize;
+        while slot < V16_MAX_PORTFOLIO_ASSETS_N {
+            remaining_initial[slot] = legs[slot].base_initial;
+            remaining_maintenance[slot] = legs[slot].base_maintenance;
+            slot += 1;
+        }
+        let mut initial_credit = 0u128;
+        let mut maintenance_credit = 0u128;
+        let mut i = 0usize;
+        while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            let offset = self.header.portfolio_margin_offsets[i].try_to_runtime()?;
+            i += 1;
+            if offset.offset_bps == 0 {
+                continue;
+            }
+            let mut slot_a = V16_MAX_PORTFOLIO_ASSETS_N;
+            let mut slot_b = V16_MAX_PORTFOLIO_ASSETS_N;
+            let mut slot = 0usize;
+            while slot < V16_MAX_PORTFOLIO_ASSETS_N {
+                if legs[slot].active {
+                    if legs[slot].asset_index == offset.asset_a as usize {
+                        slot_a = slot;
+                    } else if legs[slot].asset_index == offset.asset_b as usize {
+                        slot_b = slot;
+                    }
+                }
+                slot += 1;
+            }
+            if slot_a == V16_MAX_PORTFOLIO_ASSETS_N
+                || slot_b == V16_MAX_PORTFOLIO_ASSETS_N
+                || legs[slot_a].side == legs[slot_b].side
+            {
+                continue;
+            }
+            let matched_initial = remaining_initial[slot_a].min(remaining_initial[slot_b]);
+            let matched_maintenance =
+                remaining_maintenance[slot_a].min(remaining_maintenance[slot_b]);
+            remaining_initial[slot_a] -= matched_initial;
+            remaining_initial[slot_b] -= matched_initial;
+            remaining_maintenance[slot_a] -= matched_maintenance;
+            remaining_maintenance[slot_b] -= matched_maintenance;
+            let leg_initial_credit = wide_mul_div_floor_u128(
+                matched_initial,
+                offset.offset_bps as u128,
+                MAX_MARGIN_BPS as u128,
+            );
+            let leg_maintenance_credit = wide_mul_div_floor_u128(
+                matched_maintenance,
+                offset.offset_bps as u128,
+                MAX_MARGIN_BPS as u128,
+            );
+            initial_credit = initial_credit
+                .checked_add(leg_initial_credit)
+                .and_then(|v| v.checked_add(leg_initial_credit))
+                .ok_or(V16Error::ArithmeticOverflow)?;
+            maintenance_credit = maintenance_credit
+                .checked_add(leg_maintenance_credit)
+                .and_then(|v| v.checked_add(leg_maintenance_credit))
+                .ok_or(V16Error::ArithmeticOverflow)?;
+        }
+        Ok((initial_credit, maintenance_credit))
+    }
+
+    /// The stored portfolio-margin offset table.
+    fn portfolio_margin_offset_table(
+        &self,
+    ) -> V16Result<[PortfolioMarginOffsetV16; V16_MAX_PORTFOLIO_MARGIN_OFFSETS]> {
+        let mut offsets = [PortfolioMarginOffsetV16::EMPTY; V16_MAX_PORTFOLIO_MARGIN_OFFSETS];
+        let mut i = 0usize;
+        while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            offsets[i] = self.header.portfolio_margin_offsets[i].try_to_runtime()?;
+            i += 1;
+        }
+        Ok(offsets)
+    }
+
+    /// Checks an offset table against `config`: entries are well-formed (`asset_a < asset_b`,
+    /// unused slots all-zero), name distinct configured asset pairs, and no entry exceeds the
+    /// envelope slack of either asset's effective risk config, so an offset pair still covers one
+    /// accrual's worst-case loss.
+    fn validate_portfolio_margin_offsets(
+        &self,
+        config: V16Config,
+        profile_update: Option<AssetRiskProfileUpdateV16>,
+        offsets: &[PortfolioMarginOffsetV16; V16_MAX_PORTFOLIO_MARGIN_OFFSETS],
+    ) -> V16Result<()> {
+        let mut i = 0usize;
+        while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+            let offset =
+                PortfolioMarginOffsetV16Account::from_runtime(&offsets[i]).try_to_runtime()?;
+            if offset.offset_bps != 0 {
+                let max_a = self
+                    .asset_risk_config_after(config, profile_update, offset.asset_a as usize)?
+                    .max_portfolio_margin_offset_bps()?;
+                let max_b = self
+                    .asset_risk_config_after(config, profile_update, offset.asset_b as usize)?
+                    .max_portfolio_margin_offset_bps()?;
+                if offset.offset_bps > max_a.min(max_b) {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig
+                            .with_reason(V16ErrorReason::PortfolioMarginOffsetUnsafe),
+                    ));
+                }
+                let mut j = 0usize;
+                while j < i {
+                    let earlier = offsets[j];
+                    if earlier.offset_bps != 0
+                        && earlier.asset_a == offset.asset_a
+                        && earlier.asset_b == offset.asset_b
+                    {
+                        return Err(self.reject(
+                            V16Error::InvalidConfig
+                                .with_reason(V16ErrorReason::PortfolioMarginOffsetDuplicatePair),
+                        ));
+                    }
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn set_asset_state(&mut self, asset_index: usize, asset: AssetStateV16) -> V16Result<()> {
+        if asset_index >= self.header.config.max_market_slots.get() as usize
+            || asset_index >= self.markets.len()
+        {
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
+        }
         let old_blockers =
             slot_resolved_payout_blockers_v16(self.markets[asset_index].engine_slot())?;
         let new_blockers = {
@@ -10387,7 +13516,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if asset_index >= self.header.config.max_market_slots.get() as usize
             || asset_index >= self.markets.len()
         {
-            return Err(V16Error::InvalidLeg);
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+            );
         }
         Ok(())
     }
@@ -10479,6 +13610,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         canonical_asset.effective_price = old_asset.effective_price;
         canonical_asset.fund_px_last = old_asset.fund_px_last;
         canonical_asset.slot_last = old_asset.slot_last;
+        canonical_asset.resolved_slot = old_asset.resolved_slot;
+        canonical_asset.resolved_price = old_asset.resolved_price;
         let mut canonical_slot = EngineAssetSlotV16Account::empty_for_market(old_asset.market_id);
         canonical_slot.asset = AssetStateV16Account::from_runtime(&canonical_asset);
         canonical_slot
@@ -10523,6 +13656,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
 
     fn require_asset_live_reducible(&self, asset_index: usize) -> V16Result<()> {
         let asset = self.asset_state(asset_index)?;
+        if asset.resolved_slot != 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetResolved))
+            );
+        }
         match asset.lifecycle {
             AssetLifecycleV16::Active | AssetLifecycleV16::DrainOnly => Ok(()),
             _ => Err(V16Error::LockActive),
@@ -10647,7 +13785,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 .ok_or(V16Error::CounterOverflow)?,
         );
         self.as_view().validate_header_aggregate_totals()?;
-        self.validate_shape_audit_scan()
+        self.validate_shape_audit_scan()?;
+        self.emit(EngineEventV16::SideResetFinalized { asset_index, side });
+        Ok(())
     }
 
     fn account_b_loss_bound(account: &PortfolioV16View<'_>) -> V16Result<u128> {
@@ -11065,8 +14205,25 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             || request.exec_price == 0
             || request.exec_price > MAX_ORACLE_PRICE
             || request.fee_bps > config.max_trading_fee_bps
+            || request.maker_fee_bps.unsigned_abs() > config.max_trading_fee_bps
+            || (request.maker == TradeMakerV16::None && request.maker_fee_bps != 0)
+            || (request.maker_fee_bps < 0 && request.maker_fee_bps.unsigned_abs() > request.fee_bps)
         {
-            return Err(V16Error::InvalidConfig);
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::TradeRequestOutOfBounds),
+            ));
+        }
+        if (request.post_only_a && request.maker != TradeMakerV16::A)
+            || (request.post_only_b && request.maker != TradeMakerV16::B)
+        {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::TradePostOnlyWouldTake),
+            ));
+        }
+        if self.asset_state(request.asset_index)?.resolved_slot != 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetResolved))
+            );
         }
         Ok(())
     }
@@ -11104,6 +14261,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             Self::position_delta_lookup_for_asset(long_account, request.asset_index, long_delta)?;
         let short_lookup =
             Self::position_delta_lookup_for_asset(short_account, request.asset_index, short_delta)?;
+        if (request.reduce_only_a
+            && !position_delta_is_reduce_only(long_lookup.current_q, long_delta)?)
+            || (request.reduce_only_b
+                && !position_delta_is_reduce_only(short_lookup.current_q, short_delta)?)
+        {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::TradeReduceOnlyViolated),
+            ));
+        }
         let risk_increasing = position_delta_increases_risk(long_lookup.current_q, long_delta)?
             || position_delta_increases_risk(short_lookup.current_q, short_delta)?;
         let target_effective_lag = self.asset_has_target_effective_lag(request.asset_index)?;
@@ -11130,7 +14296,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             self.asset_is_loss_stale(request.asset_index)?,
             target_effective_lag,
             blocked_by_pending_domain_barrier,
-        )?;
+        )
+        .map_err(|r| self.reject(r))?;
         Ok(TradePositionPreflightV16 {
             risk_increasing,
             long_lookup,
@@ -11385,6 +14552,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         }
         account.header.legs[leg_slot] =
             PortfolioLegV16Account::from_runtime(&PortfolioLegV16::EMPTY);
+        account.header.isolated_margin[leg_slot] = IsolatedMarginV16Account::default();
         let mut bitmap = account.header.active_bitmap.map(V16PodU64::get);
         active_bitmap_clear(&mut bitmap, leg_slot)?;
         account.header.active_bitmap = bitmap.map(V16PodU64::new);
@@ -11479,7 +14647,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             } else {
                 SideV16::Short
             };
-            let leg_slot = lookup.empty_slot.ok_or(V16Error::InvalidLeg)?;
+            let leg_slot = lookup.empty_slot.ok_or_else(|| {
+                self.reject(
+                    V16Error::InvalidLeg.with_reason(V16ErrorReason::AccountLegCapacityExhausted),
+                )
+            })?;
             return self.attach_leg_at_slot(account, asset_index, side, new, leg_slot);
         }
         let leg_slot = existing_slot.ok_or(V16Error::InvalidLeg)?;
@@ -11847,6 +15019,32 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         asset_index: usize,
         bankrupt_side: SideV16,
         residual_remaining: u128,
+    ) -> V16Result<BResidualBookingOutcomeV16> {
+        let outcome = self.book_bankruptcy_residual_chunk_to_domain(
+            asset_index,
+            bankrupt_side,
+            residual_remaining,
+        )?;
+        self.forfeit_funding_spread(asset_index, outcome.booked_loss + outcome.explicit_loss)?;
+        Ok(outcome)
+    }
+
+    /// A socialized loss may include funding spread the bankrupt payer never settled, so the
+    /// same amount of the asset's pending spread is written off rather than swept later.
+    fn forfeit_funding_spread(&mut self, asset_index: usize, socialized: u128) -> V16Result<()> {
+        let mut asset = self.asset_state(asset_index)?;
+        if socialized == 0 || asset.funding_spread_unswept == 0 {
+            return Ok(());
+        }
+        asset.funding_spread_unswept -= asset.funding_spread_unswept.min(socialized);
+        self.set_asset_state(asset_index, asset)
+    }
+
+    fn book_bankruptcy_residual_chunk_to_domain(
+        &mut self,
+        asset_index: usize,
+        bankrupt_side: SideV16,
+        residual_remaining: u128,
     ) -> V16Result<BResidualBookingOutcomeV16> {
         self.validate_configured_asset_index(asset_index)?;
         if residual_remaining == 0 {
@@ -12060,6 +15258,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             outcome.booked_loss,
             outcome.explicit_loss,
         )?;
+        self.emit(EngineEventV16::ResidualBooked {
+            asset_index,
+            bankrupt_side,
+            outcome,
+        });
         Ok(outcome)
     }
 
@@ -12129,6 +15332,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 .checked_add(1)
                 .ok_or(V16Error::CounterOverflow)?,
         );
+        self.emit(EngineEventV16::SideResetBegun { asset_index, side });
         Ok(())
     }
 
@@ -12210,20 +15414,213 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.reduce_matching_open_interest_for_unilateral_close(asset_index, leg.side, close_q)
     }
 
+    fn liquidation_fee_for_close(
+        &self,
+        asset_index: usize,
+        close_q: u128,
+        fee_bps: u64,
+    ) -> V16Result<u128> {
+        let config = self.header.config.try_to_runtime_shape()?;
+        let fee_notional =
+            risk_notional_ceil(close_q, self.asset_state(asset_index)?.effective_price)?;
+        Ok(checked_fee_bps(fee_notional, fee_bps)?
+            .max(config.min_liquidation_abs)
+            .min(config.liquidation_fee_cap))
+    }
+
+    /// Rate charged now against a full liquidation rate of `full_bps`: the auction starts at
+    /// `liquidation_auction_start_bps` of it on the account's `liquidatable_since_slot` and
+    /// reaches it after `liquidation_auction_ramp_slots`. Without a ramp it is `full_bps`.
+    pub fn liquidation_auction_fee_bps(
+        &self,
+        account: &PortfolioV16View<'_>,
+        full_bps: u64,
+    ) -> V16Result<u64> {
+        let config = self.header.config.try_to_runtime_shape()?;
+        let ramp = config.liquidation_auction_ramp_slots;
+        if ramp == 0 {
+            return Ok(full_bps);
+        }
+        let since = account.header.liquidatable_since_slot.get();
+        let elapsed = if since == 0 {
+            0
+        } else {
+            self.header
+                .current_slot
+                .get()
+                .saturating_sub(since)
+                .min(ramp)
+        };
+        let start = config.liquidation_auction_start_bps as u128;
+        let share = (MAX_MARGIN_BPS as u128 - start)
+            .checked_mul(elapsed as u128)
+            .map(|v| v / ramp as u128)
+            .and_then(|v| v.checked_add(start))
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        let bps = (full_bps as u128)
+            .checked_mul(share)
+            .ok_or(V16Error::ArithmeticOverflow)?
+            / MAX_MARGIN_BPS as u128;
+        u64::try_from(bps).map_err(|_| V16Error::ArithmeticOverflow)
+    }
+
+    /// Smallest `close_q` on `asset_index` after which the liquidated margin is back above
+    /// maintenance, net of the liquidation fee at `fee_bps` (floored by `min_liquidation_abs`,
+    /// capped by `liquidation_fee_cap`). For an isolated leg that is the leg's allocation,
+    /// otherwise the shared pool. Returns the full position when no partial close restores it.
+    /// `account` must already be settled to the current K/F, as after a refresh; closes happen
+    /// at the effective price, so a close only moves requirements and the fee.
+    pub fn liquidation_close_q_to_restore(
+        &self,
+        account: &PortfolioV16View<'_>,
+        asset_index: usize,
+        fee_bps: u64,
+    ) -> V16Result<u128> {
+        let leg_slot = Self::require_active_leg_slot_for_asset(account, asset_index)?;
+        let abs_q = account.header.legs[leg_slot]
+            .try_to_runtime()?
+            .basis_pos_q
+            .unsigned_abs();
+        if abs_q == 0 {
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::LiquidationLegEmpty))
+            );
+        }
+        let isolated_deficit = self.isolated_margin_deficit(account)?;
+        if !self.liquidation_close_restores(account, leg_slot, abs_q, fee_bps, isolated_deficit)? {
+            return Ok(abs_q);
+        }
+        let mut lo = 1u128;
+        let mut hi = abs_q;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.liquidation_close_restores(account, leg_slot, mid, fee_bps, isolated_deficit)? {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Ok(lo)
+    }
+
+    fn liquidation_close_restores(
+        &self,
+        account: &PortfolioV16View<'_>,
+        leg_slot: usize,
+        close_q: u128,
+        fee_bps: u64,
+        isolated_deficit: u128,
+    ) -> V16Result<bool> {
+        let mut scratch = *account.header;
+        let mut leg = scratch.legs[leg_slot].try_to_runtime()?;
+        let remaining_q = i128::try_from(leg.basis_pos_q.unsigned_abs() - close_q)
+            .map_err(|_| V16Error::ArithmeticOverflow)?;
+        leg.basis_pos_q = match leg.side {
+            SideV16::Long => remaining_q,
+            SideV16::Short => -remaining_q,
+        };
+        scratch.legs[leg_slot] = PortfolioLegV16Account::from_runtime(&leg);
+        let scratch = PortfolioV16View::new(&scratch);
+        let fee = self.liquidation_fee_for_close(leg.asset_index as usize, close_q, fee_bps)?;
+        let isolated = scratch.header.isolated_margin[leg_slot].try_to_runtime()?;
+        if isolated.enabled {
+            let (_, leg_maintenance) = self.leg_margin_requirements(&scratch, leg_slot)?;
+            return Ok(isolated.equity().saturating_sub(fee) >= leg_maintenance);
+        }
+        let cert = self.compute_account_health_cert_with_price_override(&scratch, false, None)?;
+        let fee = i128::try_from(fee).map_err(|_| V16Error::ArithmeticOverflow)?;
+        let covered = cert
+            .certified_equity
+            .checked_sub(fee)
+            .and_then(|v| v.checked_add(i128::try_from(isolated_deficit).ok()?))
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        Ok(covered >= 0 && covered as u128 >= cert.certified_maintenance_req)
+    }
+
+    fn require_leg_liquidatable(
+        &self,
+        account: &PortfolioV16View<'_>,
+        leg_slot: usize,
+    ) -> V16Result<()> {
+        let cert = account.header.health_cert.try_to_runtime()?;
+        // An isolated leg is liquidated on its own allocation's shortfall, which the shared
+        // pool's surplus cannot mask; a cross leg only on the deficit left after isolated ones.
+        let isolated = account.header.isolated_margin[leg_slot].try_to_runtime()?;
+        if isolated.enabled {
+            let (_, leg_maintenance) = self.leg_margin_requirements(account, leg_slot)?;
+            if isolated.equity() >= leg_maintenance {
+                return Err(self.reject(
+                    V16Error::NonProgress
+                        .with_reason(V16ErrorReason::LiquidationIsolatedMarginHealthy),
+                ));
+            }
+        } else if cert.certified_liq_deficit == 0 {
+            return Err(self.reject(
+                V16Error::NonProgress.with_reason(V16ErrorReason::LiquidationAccountHealthy),
+            ));
+        } else if cert.certified_liq_deficit <= self.isolated_margin_deficit(account)? {
+            return Err(self.reject(
+                V16Error::NonProgress.with_reason(V16ErrorReason::LiquidationCrossMarginHealthy),
+            ));
+        }
+        Ok(())
+    }
+
+    fn debit_isolated_margin_for_liquidation(
+        account: &mut PortfolioV16ViewMut<'_>,
+        leg_slot: usize,
+        amount: u128,
+    ) -> V16Result<()> {
+        let mut isolated = account.header.isolated_margin[leg_slot].try_to_runtime()?;
+        if !isolated.enabled {
+            return Ok(());
+        }
+        isolated.settled_pnl = isolated
+            .settled_pnl
+            .checked_sub(i128::try_from(amount).map_err(|_| V16Error::ArithmeticOverflow)?)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        validate_non_min_i128(isolated.settled_pnl)?;
+        account.header.isolated_margin[leg_slot] =
+            IsolatedMarginV16Account::from_runtime(&isolated);
+        Ok(())
+    }
+
     pub fn liquidate_account_not_atomic(
         &mut self,
         account: &mut PortfolioV16ViewMut<'_>,
         request: LiquidationRequestV16,
+    ) -> V16Result<LiquidationOutcomeV16> {
+        self.liquidate_account_sized_not_atomic(account, request, false)
+    }
+
+    /// With `size_to_restore`, `request.close_q` only caps the close, which is otherwise the
+    /// `liquidation_close_q_to_restore` quantity on the refreshed account.
+    fn liquidate_account_sized_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        request: LiquidationRequestV16,
+        size_to_restore: bool,
     ) -> V16Result<LiquidationOutcomeV16> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         let config = self.header.config.try_to_runtime_shape()?;
-        if request.asset_index >= config.max_market_slots as usize
-            || request.close_q == 0
-            || request.fee_bps > config.liquidation_fee_bps.max(config.max_trading_fee_bps)
-        {
-            return Err(V16Error::InvalidConfig);
+        if request.asset_index >= config.max_market_slots as usize || request.close_q == 0 {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::LiquidationRequestOutOfBounds),
+            ));
+        }
+        // The ramp only scales the requested rate down, so both modes share one bound.
+        let max_fee_bps = self
+            .asset_risk_config(config, request.asset_index)?
+            .liquidation_fee_bps
+            .max(config.max_trading_fee_bps);
+        if request.fee_bps > max_fee_bps {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::LiquidationRequestOutOfBounds),
+            ));
         }
         self.require_asset_live_reducible(request.asset_index)?;
         self.validate_account_scalar_preflight(&account.as_view())?;
@@ -12235,11 +15632,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             false,
         )? {
             AccountRefreshCertOutcomeV16::Certified(_) => {}
-            AccountRefreshCertOutcomeV16::BChunk(_) => return Err(V16Error::BStale),
-        }
-        let cert = account.header.health_cert.try_to_runtime()?;
-        if cert.certified_liq_deficit == 0 {
-            return Err(V16Error::NonProgress);
+            AccountRefreshCertOutcomeV16::BChunk(_) => {
+                return Err(self.reject(
+                    V16Error::BStale.with_reason(V16ErrorReason::LiquidationBChunkPending),
+                ))
+            }
         }
         let before_score = self.risk_score_unchecked(&account.as_view())?;
         let leg_slot =
@@ -12248,7 +15645,17 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if !leg.active {
             return Err(V16Error::InvalidLeg);
         }
-        let close_q = request.close_q.min(leg.basis_pos_q.unsigned_abs());
+        self.require_leg_liquidatable(&account.as_view(), leg_slot)?;
+        let fee_bps = self.liquidation_auction_fee_bps(&account.as_view(), request.fee_bps)?;
+        let close_q = if size_to_restore {
+            request.close_q.min(self.liquidation_close_q_to_restore(
+                &account.as_view(),
+                request.asset_index,
+                fee_bps,
+            )?)
+        } else {
+            request.close_q.min(leg.basis_pos_q.unsigned_abs())
+        };
         let close_i128 = i128::try_from(close_q).map_err(|_| V16Error::ArithmeticOverflow)?;
         let close_delta = match leg.side {
             SideV16::Long => close_i128
@@ -12261,7 +15668,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             request.asset_index,
             close_delta,
         )? {
-            return Err(V16Error::LockActive);
+            return Err(self.reject(
+                V16Error::LockActive
+                    .with_reason(V16ErrorReason::LiquidationTouchesPendingLossBarrier),
+            ));
         }
         if liquidation_close_would_leave_uncovered_loss_with_open_risk(
             account.header.pnl.get(),
@@ -12274,21 +15684,18 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             self.declare_permissionless_recovery(
                 PermissionlessRecoveryReasonV16::ActiveBankruptCloseCannotProgress,
             )?;
-            return Err(V16Error::RecoveryRequired);
+            return Err(self.reject(
+                V16Error::RecoveryRequired.with_reason(V16ErrorReason::LiquidationRequiresRecovery),
+            ));
         }
         self.preflight_liquidation_residual_durability(
             request.asset_index,
             leg.side,
             &account.as_view(),
         )?;
-        let fee_notional = risk_notional_ceil(
-            close_q,
-            self.asset_state(request.asset_index)?.effective_price,
-        )?;
-        let fee = checked_fee_bps(fee_notional, request.fee_bps)?
-            .max(config.min_liquidation_abs)
-            .min(config.liquidation_fee_cap);
+        let fee = self.liquidation_fee_for_close(request.asset_index, close_q, fee_bps)?;
         let charged_fee = self.charge_account_fee_not_atomic(account, fee)?;
+        Self::debit_isolated_margin_for_liquidation(account, leg_slot, charged_fee)?;
         self.settle_negative_pnl_from_principal_core_not_atomic(account)?;
         let gross_bankruptcy_residual = if account.header.pnl.get() < 0 {
             account.header.pnl.get().unsigned_abs()
@@ -12343,13 +15750,172 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.validate_liquidation_progress_from_score(before_score, &account.as_view())?;
         self.validate_shape_audit_scan()?;
         self.validate_account_audit_scan(&account.as_view())?;
-        Ok(LiquidationOutcomeV16 {
+        let outcome = LiquidationOutcomeV16 {
             closed_q: close_q,
             insurance_used,
             residual_booked: booked,
             explicit_loss: explicit,
             fee_charged: charged_fee,
-        })
+        };
+        self.emit(EngineEventV16::Liquidation {
+            asset_index: request.asset_index,
+            outcome,
+        });
+        Ok(outcome)
+    }
+
+    /// Liquidates by handing `close_q` of the leg to a backstop `liquidator` instead of closing
+    /// it against open interest. The liquidator takes the position over at the effective price
+    /// and is paid `backstop_liquidation_discount_bps` of the closed notional out of the
+    /// liquidated capital, floored and capped like the liquidation fee, so the solvency envelope
+    /// already covers it. `close_q` must be nonzero; `liquidation_close_q_to_restore` sizes the
+    /// minimal close with that discount in place of the fee.
+    /// The liquidator must hold initial margin afterwards. An account whose loss exceeds its
+    /// capital is rejected so the host falls back to `liquidate_account_not_atomic`, which books
+    /// the residual against the opposite side (ADL).
+    pub fn liquidate_by_transfer_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        liquidator: &mut PortfolioV16ViewMut<'_>,
+        asset_index: usize,
+        close_q: u128,
+    ) -> V16Result<LiquidationOutcomeV16> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        let config = self.header.config.try_to_runtime_shape()?;
+        if asset_index >= config.max_market_slots as usize || close_q == 0 {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::LiquidationRequestOutOfBounds),
+            ));
+        }
+        self.require_asset_live_reducible(asset_index)?;
+        self.validate_account_scalar_preflight(&account.as_view())?;
+        Self::require_active_leg_slot_for_asset(&account.as_view(), asset_index)?;
+        match self.refresh_account_and_certify_not_atomic(
+            account,
+            None,
+            self.header.config.public_b_chunk_atoms.get(),
+            false,
+        )? {
+            AccountRefreshCertOutcomeV16::Certified(_) => {}
+            AccountRefreshCertOutcomeV16::BChunk(_) => {
+                return Err(self.reject(
+                    V16Error::BStale.with_reason(V16ErrorReason::LiquidationBChunkPending),
+                ))
+            }
+        }
+        self.settle_account_for_position_action_and_refresh_not_atomic(liquidator)?;
+        let locked = self.h_lock_lane(
+            Some(&liquidator.as_view()),
+            false,
+            #[cfg(feature = "fork-facade")]
+            None,
+        )? == HLockLaneV16::HMax;
+        let before_score = self.risk_score_unchecked(&account.as_view())?;
+        let leg_slot = Self::require_active_leg_slot_for_asset(&account.as_view(), asset_index)?;
+        let leg = account.header.legs[leg_slot].try_to_runtime()?;
+        if !leg.active {
+            return Err(
+                self.reject(V16Error::InvalidLeg.with_reason(V16ErrorReason::LiquidationLegEmpty))
+            );
+        }
+        self.require_leg_liquidatable(&account.as_view(), leg_slot)?;
+        let discount_bps = self.liquidation_auction_fee_bps(
+            &account.as_view(),
+            config.backstop_liquidation_discount_bps.min(
+                self.asset_risk_config(config, asset_index)?
+                    .liquidation_fee_bps,
+            ),
+        )?;
+        let close_q = close_q.min(leg.basis_pos_q.unsigned_abs());
+        self.settle_negative_pnl_from_principal_core_not_atomic(account)?;
+        if account.header.pnl.get() < 0 {
+            return Err(self.reject(
+                V16Error::NonProgress.with_reason(V16ErrorReason::LiquidationTransferBankrupt),
+            ));
+        }
+        let discount = self
+            .liquidation_fee_for_close(asset_index, close_q, discount_bps)?
+            .min(account.header.capital.get());
+        if discount != 0 {
+            let vault = self.header.vault.get();
+            account.header.capital = V16PodU128::new(
+                account
+                    .header
+                    .capital
+                    .get()
+                    .checked_sub(discount)
+                    .ok_or(V16Error::CounterUnderflow)?,
+            );
+            liquidator.header.capital = V16PodU128::new(
+                liquidator
+                    .header
+                    .capital
+                    .get()
+                    .checked_add(discount)
+                    .ok_or(V16Error::ArithmeticOverflow)?,
+            );
+            TokenValueFlowProofV16::account_capital_to_account_capital(discount, vault, vault)?
+                .validate()?;
+            account.header.health_cert.valid = 0;
+            liquidator.header.health_cert.valid = 0;
+            Self::debit_isolated_margin_for_liquidation(account, leg_slot, discount)?;
+        }
+        let close_i128 = i128::try_from(close_q).map_err(|_| V16Error::ArithmeticOverflow)?;
+        let request = TradeRequestV16 {
+            // The liquidated side may only shrink; it is the second account when long.
+            reduce_only_a: leg.side == SideV16::Short,
+            reduce_only_b: leg.side == SideV16::Long,
+            ..TradeRequestV16::new(
+                asset_index,
+                close_i128,
+                self.asset_state(asset_index)?.effective_price,
+                0,
+            )
+        };
+        self.validate_trade_request(request)?;
+        let (applied, liquidator_has_source_claims) = match leg.side {
+            SideV16::Long => {
+                let applied =
+                    self.apply_trade_after_refresh_not_atomic(liquidator, account, request, false)?;
+                (applied, applied.long_has_source_claims)
+            }
+            SideV16::Short => {
+                let applied =
+                    self.apply_trade_after_refresh_not_atomic(account, liquidator, request, false)?;
+                (applied, applied.short_has_source_claims)
+            }
+        };
+        self.certify_account_after_local_settlement_with_price_override(account, None)?;
+        self.certify_account_after_local_settlement_with_price_override(liquidator, None)?;
+        if applied.risk_increasing && !locked && liquidator_has_source_claims {
+            self.create_initial_margin_source_lien_if_needed(liquidator)?;
+        }
+        Self::ensure_initial_margin(&liquidator.as_view()).map_err(|r| self.reject(r))?;
+        if locked {
+            Self::ensure_no_positive_credit_initial_margin(&liquidator.as_view())
+                .map_err(|r| self.reject(r))?;
+        }
+        self.validate_liquidation_progress_from_score(before_score, &account.as_view())?;
+        self.validate_shape_audit_scan()?;
+        self.validate_account_audit_scan(&account.as_view())?;
+        self.validate_account_audit_scan(&liquidator.as_view())?;
+        let outcome = LiquidationOutcomeV16 {
+            closed_q: close_q,
+            insurance_used: 0,
+            residual_booked: 0,
+            explicit_loss: 0,
+            fee_charged: discount,
+        };
+        self.emit_fill(request, applied);
+        self.emit(EngineEventV16::Liquidation {
+            asset_index,
+            outcome,
+        });
+        Ok(outcome)
     }
 
     pub fn rebalance_reduce_position_not_atomic(
@@ -12358,7 +15924,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         request: RebalanceRequestV16,
     ) -> V16Result<RebalanceOutcomeV16> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         if request.asset_index >= self.header.config.max_market_slots.get() as usize
             || request.reduce_q == 0
@@ -12425,21 +15993,23 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         }
     }
 
-    fn ensure_initial_margin(account: &PortfolioV16View<'_>) -> V16Result<()> {
+    fn ensure_initial_margin(account: &PortfolioV16View<'_>) -> Result<(), V16Rejection> {
         let cert = account.header.health_cert.try_to_runtime()?;
         if !cert.valid {
-            return Err(V16Error::Stale);
+            return Err(V16Error::Stale.with_reason(V16ErrorReason::InitialMarginCertStale));
         }
         let equity = cert.certified_equity;
         if equity < 0 || (equity as u128) < cert.certified_initial_req {
-            return Err(V16Error::InvalidConfig);
+            return Err(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::InitialMarginInsufficient)
+            );
         }
         Ok(())
     }
 
     #[cfg(kani)]
     pub fn kani_ensure_initial_margin(account: &PortfolioV16View<'_>) -> V16Result<()> {
-        Self::ensure_initial_margin(account)
+        Self::ensure_initial_margin(account).map_err(V16Error::from)
     }
 
     fn account_no_positive_credit_equity(account: &PortfolioV16View<'_>) -> V16Result<i128> {
@@ -12455,11 +16025,14 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             .ok_or(V16Error::ArithmeticOverflow)
     }
 
-    fn ensure_no_positive_credit_initial_margin(account: &PortfolioV16View<'_>) -> V16Result<()> {
+    fn ensure_no_positive_credit_initial_margin(
+        account: &PortfolioV16View<'_>,
+    ) -> Result<(), V16Rejection> {
         let equity = Self::account_no_positive_credit_equity(account)?;
         let cert = account.header.health_cert.try_to_runtime()?;
         if equity < 0 || (equity as u128) < cert.certified_initial_req {
-            return Err(V16Error::LockActive);
+            return Err(V16Error::LockActive
+                .with_reason(V16ErrorReason::InitialMarginRequiresPositiveCredit));
         }
         Ok(())
     }
@@ -12468,7 +16041,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     pub fn kani_ensure_no_positive_credit_initial_margin(
         account: &PortfolioV16View<'_>,
     ) -> V16Result<()> {
-        Self::ensure_no_positive_credit_initial_margin(account)
+        Self::ensure_no_positive_credit_initial_margin(account).map_err(V16Error::from)
     }
 
     fn recertify_account_after_source_lien_change(
@@ -12500,7 +16073,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             active_bitmap_at_cert: account.header.active_bitmap.map(V16PodU64::get),
             valid: true,
         };
-        account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+        self.store_health_cert(account, &cert)?;
         Ok(cert)
     }
 
@@ -12519,39 +16092,28 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             return Err(V16Error::InvalidConfig);
         }
         let asset = self.asset_state(asset_index)?;
-        if asset.raw_oracle_target_price != asset.effective_price {
+        if asset.raw_oracle_target_price != asset.effective_price
+            || self.portfolio_margin_offsets_configured()?
+            || Self::account_has_isolated_margin(&account.as_view())?
+            || account.header.account_initial_margin_bps.get() != 0
+        {
             let cert = self.compute_account_health_cert_with_price_override(
                 &account.as_view(),
                 false,
                 None,
             )?;
-            account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+            self.store_health_cert(account, &cert)?;
             return Ok(cert);
         }
         let existing = account.header.health_cert.try_to_runtime()?;
         let old_notional = risk_notional_ceil(old_abs_q, price)?;
         let new_notional = risk_notional_ceil(new_abs_q, price)?;
-        let config = self.header.config.try_to_runtime_shape()?;
-        let old_initial = margin_requirement(
-            old_notional,
-            config.initial_margin_bps,
-            config.min_nonzero_im_req,
-        )?;
-        let old_maintenance = margin_requirement(
-            old_notional,
-            config.maintenance_margin_bps,
-            config.min_nonzero_mm_req,
-        )?;
-        let new_initial = margin_requirement(
-            new_notional,
-            config.initial_margin_bps,
-            config.min_nonzero_im_req,
-        )?;
-        let new_maintenance = margin_requirement(
-            new_notional,
-            config.maintenance_margin_bps,
-            config.min_nonzero_mm_req,
-        )?;
+        let config =
+            self.asset_risk_config(self.header.config.try_to_runtime_shape()?, asset_index)?;
+        let old_initial = config.initial_requirement(old_notional)?;
+        let old_maintenance = config.maintenance_requirement(old_notional)?;
+        let new_initial = config.initial_requirement(new_notional)?;
+        let new_maintenance = config.maintenance_requirement(new_notional)?;
         let initial_req = existing
             .certified_initial_req
             .checked_sub(old_initial)
@@ -12586,7 +16148,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             active_bitmap_at_cert: account.header.active_bitmap.map(V16PodU64::get),
             valid: true,
         };
-        account.header.health_cert = HealthCertV16Account::from_runtime(&cert);
+        self.store_health_cert(account, &cert)?;
         Ok(cert)
     }
 
@@ -12608,9 +16170,45 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             self.require_asset_active_for_risk_increase(request.asset_index)?;
         }
         let notional = trade_notional_floor(abs_size_q, request.exec_price)?;
-        let fee = checked_fee_bps(notional, request.fee_bps)?;
-        let fee_a = self.charge_account_fee_current_not_atomic(long_account, fee)?;
-        let fee_b = self.charge_account_fee_current_not_atomic(short_account, fee)?;
+        let taker_fee = checked_fee_bps(notional, request.fee_bps)?;
+        let maker_fee = checked_fee_bps(notional, request.maker_fee_bps.max(0) as u64)?;
+        let (fee_a, fee_b) = match request.maker {
+            TradeMakerV16::None => (taker_fee, taker_fee),
+            TradeMakerV16::A => (maker_fee, taker_fee),
+            TradeMakerV16::B => (taker_fee, maker_fee),
+        };
+        let fee_a = self.record_fill_volume_and_discount_fee(long_account, notional, fee_a)?;
+        let fee_b = self.record_fill_volume_and_discount_fee(short_account, notional, fee_b)?;
+        let fee_a = self.charge_account_fee_current_not_atomic(long_account, fee_a)?;
+        let fee_b = self.charge_account_fee_current_not_atomic(short_account, fee_b)?;
+        let taker_fee_collected = match request.maker {
+            TradeMakerV16::None => 0,
+            TradeMakerV16::A => fee_b,
+            TradeMakerV16::B => fee_a,
+        };
+        let rebate = if request.maker_fee_bps < 0 {
+            checked_fee_bps(notional, request.maker_fee_bps.unsigned_abs())?
+                .min(taker_fee_collected)
+        } else {
+            0
+        };
+        let (rebate_a, rebate_b) = match request.maker {
+            TradeMakerV16::A if rebate != 0 => {
+                self.pay_maker_rebate_not_atomic(long_account, rebate, taker_fee_collected)?;
+                (rebate, 0)
+            }
+            TradeMakerV16::B if rebate != 0 => {
+                self.pay_maker_rebate_not_atomic(short_account, rebate, taker_fee_collected)?;
+                (0, rebate)
+            }
+            _ => (0, 0),
+        };
+        if self.header.config.funding_twap_window_slots.get() != 0 {
+            let mut asset = self.asset_state(request.asset_index)?;
+            asset.funding_mark_price = request.exec_price;
+            asset.funding_mark_slot = asset.slot_last;
+            self.set_asset_state(request.asset_index, asset)?;
+        }
         self.apply_current_position_delta_with_lookup(
             long_account,
             request.asset_index,
@@ -12649,6 +16247,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(TradeApplyOutcomeV16 {
             fee_a,
             fee_b,
+            rebate_a,
+            rebate_b,
             notional,
             risk_increasing,
             long_has_source_claims: trade_preflight.long_has_source_claims,
@@ -12656,6 +16256,18 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         })
     }
 
+    /// Reports a fill once every check that could still reject it has passed.
+    fn emit_fill(&mut self, request: TradeRequestV16, applied: TradeApplyOutcomeV16) {
+        self.emit(EngineEventV16::Fill {
+            asset_index: request.asset_index,
+            size_q: request.size_q,
+            exec_price: request.exec_price,
+            notional: applied.notional,
+            fee_long: applied.fee_a,
+            fee_short: applied.fee_b,
+        });
+    }
+
     fn accumulate_batch_trade_apply(
         outcome: &mut BatchTradeOutcomeV16,
         risk_increasing: &mut bool,
@@ -12675,6 +16287,14 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             .fee_b
             .checked_add(applied.fee_b)
             .ok_or(V16Error::ArithmeticOverflow)?;
+        outcome.rebate_a = outcome
+            .rebate_a
+            .checked_add(applied.rebate_a)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        outcome.rebate_b = outcome
+            .rebate_b
+            .checked_add(applied.rebate_b)
+            .ok_or(V16Error::ArithmeticOverflow)?;
         outcome.notional = outcome
             .notional
             .checked_add(applied.notional)
@@ -12706,6 +16326,8 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             TradeApplyOutcomeV16 {
                 fee_a,
                 fee_b,
+                rebate_a: 0,
+                rebate_b: 0,
                 notional,
                 risk_increasing: applied_risk_increasing,
                 long_has_source_claims: applied_long_has_source_claims,
@@ -12731,11 +16353,13 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 self.create_initial_margin_source_lien_if_needed(short_account)?;
             }
         }
-        Self::ensure_initial_margin(&long_account.as_view())?;
-        Self::ensure_initial_margin(&short_account.as_view())?;
+        Self::ensure_initial_margin(&long_account.as_view()).map_err(|r| self.reject(r))?;
+        Self::ensure_initial_margin(&short_account.as_view()).map_err(|r| self.reject(r))?;
         if locked {
-            Self::ensure_no_positive_credit_initial_margin(&long_account.as_view())?;
-            Self::ensure_no_positive_credit_initial_margin(&short_account.as_view())?;
+            Self::ensure_no_positive_credit_initial_margin(&long_account.as_view())
+                .map_err(|r| self.reject(r))?;
+            Self::ensure_no_positive_credit_initial_margin(&short_account.as_view())
+                .map_err(|r| self.reject(r))?;
         }
         self.validate_shape_audit_scan()?;
         self.validate_account_audit_scan(&long_account.as_view())?;
@@ -12767,6 +16391,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         short_account: &mut PortfolioV16ViewMut<'_>,
         requests: &[TradeRequestV16],
     ) -> V16Result<BatchTradeOutcomeV16> {
+        if self.admission.is_none() {
+            let admission = self.default_warmup_admission();
+            return self.execute_batch_with_admission_not_atomic(
+                long_account,
+                short_account,
+                requests,
+                admission,
+            );
+        }
         self.validate_unconfigured_market_tail()?;
         let mut ignore_unrelated_loss_stale =
             decode_bool(self.header.loss_stale_active)? && !requests.is_empty();
@@ -12893,7 +16526,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         threshold_bps_opt: Option<u128>,
     ) -> V16Result<BatchTradeOutcomeV16> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         let config = self.header.config.try_to_runtime_shape()?;
         if requests.is_empty() {
@@ -12924,12 +16559,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             fill_count: 0,
             fee_a: 0,
             fee_b: 0,
+            rebate_a: 0,
+            rebate_b: 0,
             notional: 0,
         };
         let mut risk_increasing = false;
         let mut long_has_source_claims = false;
         let mut short_has_source_claims = false;
         let recertify_after_fill = requests.len() == 1;
+        let mut fills = [None; V16_MAX_PORTFOLIO_ASSETS_N];
         let mut i = 0usize;
         while i < requests.len() {
             let applied = self.apply_trade_after_refresh_not_atomic(
@@ -12938,6 +16576,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 requests[i],
                 recertify_after_fill,
             )?;
+            *fills.get_mut(i).ok_or(V16Error::InvalidConfig)? = Some(applied);
             Self::accumulate_batch_trade_apply(
                 &mut outcome,
                 &mut risk_increasing,
@@ -12959,6 +16598,13 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             long_has_source_claims,
             short_has_source_claims,
         )?;
+        let mut i = 0usize;
+        while i < requests.len() {
+            if let Some(applied) = fills[i] {
+                self.emit_fill(requests[i], applied);
+            }
+            i += 1;
+        }
         Ok(outcome)
     }
 
@@ -12969,7 +16615,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         requests: &[TradeRequestV16],
     ) -> V16Result<BatchTradeOutcomeV16> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         let config = self.header.config.try_to_runtime_shape()?;
         if requests.is_empty() {
@@ -13002,12 +16650,15 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             fill_count: 0,
             fee_a: 0,
             fee_b: 0,
+            rebate_a: 0,
+            rebate_b: 0,
             notional: 0,
         };
         let mut risk_increasing = false;
         let mut long_has_source_claims = false;
         let mut short_has_source_claims = false;
         let recertify_after_fill = requests.len() == 1;
+        let mut fills = [None; V16_MAX_PORTFOLIO_ASSETS_N];
         let mut i = 0usize;
         while i < requests.len() {
             let applied = self.apply_trade_after_refresh_not_atomic(
@@ -13016,6 +16667,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 requests[i],
                 recertify_after_fill,
             )?;
+            *fills.get_mut(i).ok_or(V16Error::InvalidConfig)? = Some(applied);
             Self::accumulate_batch_trade_apply(
                 &mut outcome,
                 &mut risk_increasing,
@@ -13037,6 +16689,13 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             long_has_source_claims,
             short_has_source_claims,
         )?;
+        let mut i = 0usize;
+        while i < requests.len() {
+            if let Some(applied) = fills[i] {
+                self.emit_fill(requests[i], applied);
+            }
+            i += 1;
+        }
         Ok(outcome)
     }
 
@@ -13121,7 +16780,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             return Ok(());
         }
         if decode_market_mode(self.header.mode)? != MarketModeV16::Resolved {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotResolved))
+            );
         }
         let Some((asset_index, bankrupt_side)) =
             self.resolved_bankruptcy_attribution(&account.as_view())?
@@ -13283,9 +16944,117 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(charged)
     }
 
-    #[cfg(kani)]
-    pub fn kani_charge_account_fee_current_not_atomic(
-        &mut self,
+    /// Decays the account's traded notional to the current slot, discounts `fee` by the tier that
+    /// volume reaches, then adds this fill's notional. Without fee tiers nothing is tracked.
+    fn record_fill_volume_and_discount_fee(
+        &self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        notional: u128,
+        fee: u128,
+    ) -> V16Result<u128> {
+        let config = self.header.config.try_to_runtime_shape()?;
+        if config.fee_tiers[0].volume_threshold == 0 {
+            return Ok(fee);
+        }
+        let now_slot = self.header.current_slot.get();
+        let window = config.fee_volume_window_slots;
+        let elapsed = now_slot.saturating_sub(account.header.traded_notional_volume_slot.get());
+        let volume = if elapsed >= window {
+            0
+        } else {
+            wide_mul_div_floor_u128(
+                account.header.traded_notional_volume.get(),
+                (window - elapsed) as u128,
+                window as u128,
+            )
+        };
+        let discount_bps = config.fee_tier_discount_bps(volume);
+        account.header.traded_notional_volume = V16PodU128::new(volume.saturating_add(notional));
+        account.header.traded_notional_volume_slot = V16PodU64::new(now_slot);
+        Ok(wide_mul_div_floor_u128(
+            fee,
+            (MAX_MARGIN_BPS - discount_bps) as u128,
+            MAX_MARGIN_BPS as u128,
+        ))
+    }
+
+    /// Pays the maker's rebate out of insurance. The rebate may not exceed the taker fee the same
+    /// fill collected, so a fill can only leave insurance unchanged or better off.
+    fn pay_maker_rebate_not_atomic(
+        &mut self,
+        maker: &mut PortfolioV16ViewMut<'_>,
+        rebate: u128,
+        taker_fee_collected: u128,
+    ) -> V16Result<()> {
+        if rebate > taker_fee_collected {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::TradeMakerRebateExceedsTakerFee),
+            ));
+        }
+        if rebate == 0 {
+            return Ok(());
+        }
+        let (next_insurance, next_c_tot, next_capital) = Self::maker_rebate_delta(
+            self.header.insurance.get(),
+            self.header.insurance_domain_budget_remaining_total.get(),
+            self.header.c_tot.get(),
+            maker.header.capital.get(),
+            rebate,
+            taker_fee_collected,
+        )?;
+        let vault = self.header.vault.get();
+        self.header.insurance = V16PodU128::new(next_insurance);
+        self.header.c_tot = V16PodU128::new(next_c_tot);
+        maker.header.capital = V16PodU128::new(next_capital);
+        TokenValueFlowProofV16::insurance_capital_to_account_capital(rebate, vault, vault)?
+            .validate()?;
+        maker.header.health_cert.valid = 0;
+        Ok(())
+    }
+
+    fn maker_rebate_delta(
+        insurance: u128,
+        budget_remaining: u128,
+        c_tot: u128,
+        capital: u128,
+        rebate: u128,
+        taker_fee_collected: u128,
+    ) -> V16Result<(u128, u128, u128)> {
+        if rebate > taker_fee_collected {
+            return Err(V16Error::InvalidConfig);
+        }
+        Self::credit_account_from_insurance_delta(
+            insurance,
+            budget_remaining,
+            c_tot,
+            capital,
+            rebate,
+        )
+    }
+
+    #[cfg(kani)]
+    pub fn kani_maker_rebate_delta(
+        insurance: u128,
+        budget_remaining: u128,
+        c_tot: u128,
+        capital: u128,
+        rebate: u128,
+        taker_fee_collected: u128,
+    ) -> V16Result<(u128, u128, u128)> {
+        Self::maker_rebate_delta(
+            insurance,
+            budget_remaining,
+            c_tot,
+            capital,
+            rebate,
+            taker_fee_collected,
+        )
+    }
+
+    #[cfg(kani)]
+    pub fn kani_charge_account_fee_current_not_atomic(
+        &mut self,
         account: &mut PortfolioV16ViewMut<'_>,
         requested_fee: u128,
     ) -> V16Result<u128> {
@@ -13328,7 +17097,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         requested_fee: u128,
     ) -> V16Result<u128> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         self.charge_account_fee_after_loss_settlement(account, requested_fee)
     }
@@ -13548,12 +17319,6 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                 .checked_add(converted)
                 .ok_or(V16Error::ArithmeticOverflow)?,
         );
-        self.header.pnl_matured_pos_tot = V16PodU128::new(
-            self.header
-                .pnl_matured_pos_tot
-                .get()
-                .saturating_sub(consumption.face_burn),
-        );
         let protocol_surplus_consumed = converted
             .checked_sub(consumption.counterparty_credit_consumed)
             .and_then(|v| v.checked_sub(consumption.insurance_credit_consumed))
@@ -13575,6 +17340,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         &mut self,
         account: &mut PortfolioV16ViewMut<'_>,
     ) -> V16Result<u128> {
+        if self.admission.is_none() {
+            let admission = self.default_warmup_admission();
+            return self
+                .convert_released_pnl_to_capital_with_admission_not_atomic(account, admission);
+        }
         self.preflight_convert_released_pnl_to_capital(&account.as_view())?;
         let converted = self.convert_released_pnl_to_capital_core_not_atomic(account)?;
         if converted != 0 {
@@ -13584,13 +17354,158 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         Ok(converted)
     }
 
+    /// Releases the part of the account's scheduled warmup reserve that has matured by the
+    /// group's current slot. Returns the amount moved into matured PnL.
+    pub fn advance_profit_warmup_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+    ) -> V16Result<u128> {
+        account.validate_with_market(&self.as_view())?;
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        let released = self.advance_account_profit_warmup(account)?;
+        if released != 0 {
+            self.validate_shape()?;
+            account.validate_with_market(&self.as_view())?;
+        }
+        Ok(released)
+    }
+
+    fn validate_warmup_admission(&self, admission: WarmupAdmissionV16) -> V16Result<()> {
+        if !admission.within(
+            self.header.config.h_min.get(),
+            self.header.config.h_max.get(),
+        ) {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::WarmupAdmissionOutOfBounds),
+            ));
+        }
+        Ok(())
+    }
+
+    fn default_warmup_admission(&self) -> WarmupAdmissionV16 {
+        WarmupAdmissionV16 {
+            admit_h_min: self.header.config.default_admit_h_min.get(),
+            admit_h_max: self.header.config.default_admit_h_max.get(),
+        }
+    }
+
+    fn with_warmup_admission<R>(
+        &mut self,
+        admission: WarmupAdmissionV16,
+        op: impl FnOnce(&mut Self) -> V16Result<R>,
+    ) -> V16Result<R> {
+        self.validate_warmup_admission(admission)?;
+        if self.admission.is_some() {
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::WarmupAdmissionScopeActive),
+            ));
+        }
+        self.admission = Some(WarmupAdmissionScopeV16 {
+            admission,
+            h_max_sticky_accounts: [None; 2],
+        });
+        let result = op(self);
+        self.admission = None;
+        result
+    }
+
+    /// Advances the account's warmup reserve and, when the instruction admits immediate
+    /// release and the residual covers it, accelerates the whole outstanding reserve.
+    fn touch_account_warmup_for_admission(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        admission: WarmupAdmissionV16,
+    ) -> V16Result<()> {
+        account.validate_with_market(&self.as_view())?;
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Ok(());
+        }
+        self.advance_account_profit_warmup(account)?;
+        let reserve_total = account.header.reserved_pnl.get();
+        if reserve_total == 0 || admission.admit_h_min != 0 {
+            return Ok(());
+        }
+        if self.h_lock_lane(
+            Some(&account.as_view()),
+            false,
+            #[cfg(feature = "fork-facade")]
+            None,
+        )? == HLockLaneV16::HMax
+        {
+            return Ok(());
+        }
+        let matured_plus_reserve = self
+            .header
+            .pnl_matured_pos_tot
+            .get()
+            .checked_add(reserve_total)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        if matured_plus_reserve <= self.residual() {
+            self.release_account_warmup_reserve(account)?;
+        }
+        Ok(())
+    }
+
+    /// `execute_batch_with_fee_loss_stale_scoped_not_atomic` with fresh positive PnL admitted
+    /// into warmup reserve under `admission`.
+    pub fn execute_batch_with_admission_not_atomic(
+        &mut self,
+        long_account: &mut PortfolioV16ViewMut<'_>,
+        short_account: &mut PortfolioV16ViewMut<'_>,
+        requests: &[TradeRequestV16],
+        admission: WarmupAdmissionV16,
+    ) -> V16Result<BatchTradeOutcomeV16> {
+        self.with_warmup_admission(admission, |market| {
+            market.touch_account_warmup_for_admission(long_account, admission)?;
+            market.touch_account_warmup_for_admission(short_account, admission)?;
+            market.execute_batch_with_fee_loss_stale_scoped_not_atomic(
+                long_account,
+                short_account,
+                requests,
+            )
+        })
+    }
+
+    /// `permissionless_crank_not_atomic` with fresh positive PnL admitted into warmup reserve
+    /// under `admission`.
+    pub fn permissionless_crank_with_admission_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        request: PermissionlessCrankRequestV16,
+        admission: WarmupAdmissionV16,
+    ) -> V16Result<PermissionlessProgressOutcomeV16> {
+        self.with_warmup_admission(admission, |market| {
+            market.touch_account_warmup_for_admission(account, admission)?;
+            market.permissionless_crank_not_atomic(account, request)
+        })
+    }
+
+    /// `convert_released_pnl_to_capital_not_atomic` after advancing, and when admitted
+    /// accelerating, the account's warmup reserve.
+    pub fn convert_released_pnl_to_capital_with_admission_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        admission: WarmupAdmissionV16,
+    ) -> V16Result<u128> {
+        self.with_warmup_admission(admission, |market| {
+            market.touch_account_warmup_for_admission(account, admission)?;
+            market.convert_released_pnl_to_capital_not_atomic(account)
+        })
+    }
+
     #[cfg(any(kani, feature = "fuzz"))]
     pub fn release_account_source_credit_liens_if_unneeded_not_atomic(
         &mut self,
         account: &mut PortfolioV16ViewMut<'_>,
     ) -> V16Result<u128> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         self.settle_account_side_effects_not_atomic(
             account,
@@ -13876,10 +17791,14 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         }
         account.validate_with_market(&self.as_view())?;
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         if !active_bitmap_is_empty(account.header.active_bitmap.map(V16PodU64::get)) {
-            return Err(V16Error::Stale);
+            return Err(
+                self.reject(V16Error::Stale.with_reason(V16ErrorReason::WithdrawWithOpenPositions))
+            );
         }
         // A `canceled` close ledger (left behind by cure_and_cancel_close) is inert:
         // validate_close_progress_ledger_with_market guarantees it carries no
@@ -13889,11 +17808,18 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         // ledger must block withdrawal.
         let close_progress = account.header.close_progress.try_to_runtime()?;
         if close_progress != CloseProgressLedgerV16::EMPTY && !close_progress.canceled {
-            return Err(V16Error::LockActive);
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::WithdrawCloseInProgress),
+            ));
         }
         self.settle_negative_pnl_from_principal_core_not_atomic(account)?;
-        if account.header.pnl.get() < 0 || amount > account.header.capital.get() {
-            return Err(V16Error::LockActive);
+        if account.header.pnl.get() < 0 {
+            return Err(self
+                .reject(V16Error::LockActive.with_reason(V16ErrorReason::WithdrawUnsettledLoss)));
+        }
+        if amount > account.header.capital.get() {
+            return Err(self
+                .reject(V16Error::LockActive.with_reason(V16ErrorReason::WithdrawExceedsCapital)));
         }
         let post_capital = account
             .header
@@ -13907,7 +17833,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             account.header.fee_credits.get(),
         )?;
         if equity_after < 0 {
-            return Err(V16Error::InvalidConfig);
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::WithdrawNegativeEquity),
+            ));
         }
 
         let vault_before = self.header.vault.get();
@@ -13938,6 +17866,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     /// MarketGroupV16 at frozen). Operates directly on the POD header. Resets a stale envelope
     /// (epoch advanced, different slot, not in active-close) before accruing; flips
     /// `threshold_stress_active` true when the accumulator crosses STRESS_ENVELOPE_TRIGGER_BPS_E9.
+    /// Price-moving accruals feed it themselves; wrappers only add consumption of their own.
     pub fn apply_stress_envelope_progress(
         &mut self,
         consumption_bps_e9: u128,
@@ -13986,12 +17915,17 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.header.threshold_stress_active = encode_bool(false);
     }
 
+    /// Verification-only mode flip into resolved mode at `resolved_slot` without a price or band.
+    /// Hosts resolve through `resolve_market_with_prices_not_atomic`.
+    #[cfg(any(kani, feature = "fuzz"))]
     pub fn resolve_market_not_atomic(&mut self, resolved_slot: u64) -> V16Result<()> {
         if decode_market_mode(self.header.mode)? == MarketModeV16::Recovery {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketInRecovery))
+            );
         }
         if resolved_slot < self.header.current_slot.get() {
-            return Err(V16Error::Stale);
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::ResolveSlotInPast)));
         }
         self.header.mode = encode_market_mode(MarketModeV16::Resolved);
         self.header.resolved_slot = V16PodU64::new(resolved_slot);
@@ -14003,6 +17937,213 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         self.validate_shape()
     }
 
+    /// Resolves the group at per-asset prices, `prices[i]` belonging to asset `i`. The ordinary
+    /// branch accrues each asset to `live_oracle_price` at `now_slot` and requires
+    /// `resolved_price` within `resolve_price_deviation_bps` of it; the degenerate branch
+    /// requires `live_oracle_price == P_last` and zero funding and skips the band. Both fold
+    /// the terminal `resolved_price - live` move into K before entering resolved mode.
+    pub fn resolve_market_with_prices_not_atomic(
+        &mut self,
+        resolve_mode: ResolveModeV16,
+        now_slot: u64,
+        prices: &[ResolveAssetPriceV16],
+    ) -> V16Result<()> {
+        match decode_market_mode(self.header.mode)? {
+            MarketModeV16::Live => {}
+            MarketModeV16::Recovery => {
+                return Err(
+                    self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketInRecovery))
+                )
+            }
+            MarketModeV16::Resolved => {
+                return Err(
+                    self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+                )
+            }
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::ResolveSlotInPast)));
+        }
+        let config = self.header.config.try_to_runtime_shape()?;
+        if prices.len() != config.max_market_slots as usize {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ResolvePriceCountMismatch),
+            ));
+        }
+        let mut i = 0usize;
+        while i < prices.len() {
+            let asset = self.asset_state(i)?;
+            match asset.lifecycle {
+                AssetLifecycleV16::Active | AssetLifecycleV16::DrainOnly
+                    if asset.resolved_slot == 0 =>
+                {
+                    self.resolve_asset_price_not_atomic(
+                        i,
+                        resolve_mode,
+                        now_slot,
+                        prices[i],
+                        config.resolve_price_deviation_bps,
+                    )?;
+                }
+                AssetLifecycleV16::Recovery => {
+                    return Err(self.reject(
+                        V16Error::LockActive.with_reason(V16ErrorReason::MarketInRecovery),
+                    ));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        self.enter_resolved_mode_at(now_slot)
+    }
+
+    /// Exits group recovery by settling every live asset at a wrapper-supplied fallback price,
+    /// `fallback_prices[i]` belonging to asset `i`. Each price must lie within
+    /// `max_recovery_fallback_deviation_bps` of the asset's frozen `P_last`; the move is folded
+    /// into K without accrual or funding, and the group enters resolved mode so accounts close
+    /// through `close_resolved_account_not_atomic`.
+    pub fn settle_recovery_at_fallback_prices_not_atomic(
+        &mut self,
+        now_slot: u64,
+        fallback_prices: &[u64],
+    ) -> V16Result<()> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Recovery {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotInRecovery))
+            );
+        }
+        let config = self.header.config.try_to_runtime_shape()?;
+        if !config.recovery_fallback_price_enabled {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::RecoveryFallbackDisabled),
+            ));
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::ResolveSlotInPast)));
+        }
+        if fallback_prices.len() != config.max_market_slots as usize {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ResolvePriceCountMismatch),
+            ));
+        }
+        let mut i = 0usize;
+        while i < fallback_prices.len() {
+            let asset = self.asset_state(i)?;
+            if matches!(
+                asset.lifecycle,
+                AssetLifecycleV16::Active
+                    | AssetLifecycleV16::DrainOnly
+                    | AssetLifecycleV16::Recovery
+            ) && asset.resolved_slot == 0
+            {
+                let frozen = asset.effective_price;
+                let lhs = (fallback_prices[i].abs_diff(frozen) as u128)
+                    .checked_mul(MAX_MARGIN_BPS as u128)
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+                let rhs = (config.max_recovery_fallback_deviation_bps as u128)
+                    .checked_mul(frozen as u128)
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+                if lhs > rhs {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig
+                            .with_reason(V16ErrorReason::RecoveryFallbackPriceOutOfBand),
+                    ));
+                }
+                self.resolve_asset_price_not_atomic(
+                    i,
+                    ResolveModeV16::Degenerate,
+                    now_slot,
+                    ResolveAssetPriceV16 {
+                        resolved_price: fallback_prices[i],
+                        live_oracle_price: frozen,
+                        funding_rate_e9: 0,
+                    },
+                    config.resolve_price_deviation_bps,
+                )?;
+            }
+            i += 1;
+        }
+        self.enter_resolved_mode_at(now_slot)
+    }
+
+    fn enter_resolved_mode_at(&mut self, now_slot: u64) -> V16Result<()> {
+        self.header.mode = encode_market_mode(MarketModeV16::Resolved);
+        self.header.resolved_slot = V16PodU64::new(now_slot);
+        self.header.current_slot = V16PodU64::new(now_slot);
+        self.header.slot_last = V16PodU64::new(now_slot);
+        self.header.loss_stale_active = 0;
+        self.clear_stress_envelope_v16();
+        self.validate_shape()
+    }
+
+    fn resolve_asset_price_not_atomic(
+        &mut self,
+        asset_index: usize,
+        resolve_mode: ResolveModeV16,
+        now_slot: u64,
+        price: ResolveAssetPriceV16,
+        resolve_price_deviation_bps: u64,
+    ) -> V16Result<()> {
+        if price.resolved_price == 0 || price.resolved_price > MAX_ORACLE_PRICE {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ResolvePriceOutOfRange),
+            ));
+        }
+        let live = match resolve_mode {
+            ResolveModeV16::Ordinary => {
+                while self.asset_state(asset_index)?.slot_last < now_slot {
+                    self.accrue_asset_to_not_atomic(
+                        asset_index,
+                        now_slot,
+                        price.live_oracle_price,
+                        price.funding_rate_e9,
+                        true,
+                    )?;
+                }
+                let live = self.asset_state(asset_index)?.effective_price;
+                if live != price.live_oracle_price {
+                    return Err(self.reject(
+                        V16Error::Stale.with_reason(V16ErrorReason::ResolveLivePriceNotSynced),
+                    ));
+                }
+                let lhs = (price.resolved_price.abs_diff(live) as u128)
+                    .checked_mul(MAX_MARGIN_BPS as u128)
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+                let rhs = (resolve_price_deviation_bps as u128)
+                    .checked_mul(live as u128)
+                    .ok_or(V16Error::ArithmeticOverflow)?;
+                if lhs > rhs {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig.with_reason(V16ErrorReason::ResolvePriceOutOfBand),
+                    ));
+                }
+                live
+            }
+            ResolveModeV16::Degenerate => {
+                let live = self.asset_state(asset_index)?.effective_price;
+                if price.live_oracle_price != live || price.funding_rate_e9 != 0 {
+                    return Err(self.reject(
+                        V16Error::InvalidConfig
+                            .with_reason(V16ErrorReason::ResolveDegenerateRequiresLastPrice),
+                    ));
+                }
+                live
+            }
+        };
+        let mut asset = self.asset_state(asset_index)?;
+        let price_delta = price.resolved_price as i128 - live as i128;
+        let a_long_i = i128::try_from(asset.a_long).map_err(|_| V16Error::ArithmeticOverflow)?;
+        let a_short_i = i128::try_from(asset.a_short).map_err(|_| V16Error::ArithmeticOverflow)?;
+        asset.k_long = add_non_min_i128(asset.k_long, checked_i128_mul(price_delta, a_long_i)?)?;
+        asset.k_short =
+            add_non_min_i128(asset.k_short, -checked_i128_mul(price_delta, a_short_i)?)?;
+        asset.effective_price = price.resolved_price;
+        asset.fund_px_last = price.resolved_price;
+        asset.raw_oracle_target_price = price.resolved_price;
+        asset.slot_last = now_slot;
+        self.set_asset_state(asset_index, asset)
+    }
+
     // A resolved-payout receipt that has been paid its full entitlement at the TERMINAL
     // payout rate (no unreceipted bound remains, so the rate can no longer rise) holds
     // only unrecoverable insolvency bad debt: the haircut shortfall (face - paid) is not
@@ -14107,7 +18248,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
             return Ok(0);
         }
         // Terminal: PnL reservations no longer gate realization.
-        account.header.reserved_pnl = V16PodU128::new(0);
+        self.release_account_warmup_reserve(account)?;
         let converted = self.convert_released_pnl_to_capital_core_not_atomic(account)?;
         // If the payout snapshot was captured before this account realized (another
         // winner closed first), the realized face is still counted in the ledger's
@@ -14228,7 +18369,9 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         fee_rate_per_slot: u128,
     ) -> V16Result<ResolvedCloseOutcomeV16> {
         if decode_market_mode(self.header.mode)? != MarketModeV16::Resolved {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotResolved))
+            );
         }
         if let PermissionlessProgressOutcomeV16::AccountBChunk(_) = self
             .settle_account_side_effects_not_atomic(
@@ -14242,7 +18385,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if self
             .resolved_unattributed_insolvent_negative_pnl_requires_recovery(&account.as_view())?
         {
-            return Err(V16Error::RecoveryRequired);
+            return Err(self.reject(
+                V16Error::RecoveryRequired
+                    .with_reason(V16ErrorReason::ResolvedCloseRequiresRecovery),
+            ));
         }
         self.sync_account_fee_to_slot_not_atomic(
             account,
@@ -14252,7 +18398,10 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if self
             .resolved_unattributed_insolvent_negative_pnl_requires_recovery(&account.as_view())?
         {
-            return Err(V16Error::RecoveryRequired);
+            return Err(self.reject(
+                V16Error::RecoveryRequired
+                    .with_reason(V16ErrorReason::ResolvedCloseRequiresRecovery),
+            ));
         }
         self.settle_negative_pnl_from_principal_not_atomic(account)?;
         if account.header.pnl.get() < 0 {
@@ -14329,6 +18478,11 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         .validate()?;
         self.validate_shape()?;
         account.validate_with_market(&self.as_view())?;
+        self.emit(EngineEventV16::ResolvedPayout {
+            capital_paid,
+            resolved_paid,
+            payout,
+        });
         Ok(ResolvedCloseOutcomeV16::Closed { payout })
     }
 
@@ -14371,62 +18525,489 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
                     return Ok(());
                 }
             }
-            slot += 1;
+            slot += 1;
+        }
+
+        let mut slot = 0usize;
+        while slot < V16_MAX_PORTFOLIO_ASSETS_N {
+            let leg = account.header.legs[slot].try_to_runtime()?;
+            if leg.active {
+                self.clear_leg(account, leg.asset_index as usize)?;
+            }
+            slot += 1;
+        }
+        Ok(())
+    }
+
+    pub fn deposit_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        amount: u128,
+    ) -> V16Result<()> {
+        account.validate_with_market(&self.as_view())?;
+        if amount == 0 {
+            return Ok(());
+        }
+        let vault_before = self.header.vault.get();
+        let capital = account
+            .header
+            .capital
+            .get()
+            .checked_add(amount)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        let c_tot = self
+            .header
+            .c_tot
+            .get()
+            .checked_add(amount)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        let vault = self
+            .header
+            .vault
+            .get()
+            .checked_add(amount)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        TokenValueFlowProofV16::external_in_to_account_capital(amount, vault_before, vault)?
+            .validate()?;
+        account.header.capital = V16PodU128::new(capital);
+        account.header.health_cert.valid = 0;
+        self.header.c_tot = V16PodU128::new(c_tot);
+        self.header.vault = V16PodU128::new(vault);
+        account.validate_with_market(&self.as_view())?;
+        self.validate_shape()
+    }
+
+    /// Resolves a single asset inside a live group at `price.resolved_price`, leaving every
+    /// other asset trading. The asset is accrued to its live price at `now_slot`, the resolved
+    /// price must lie within `resolve_price_deviation_bps` of it, and the terminal move is folded
+    /// into K. The asset is then frozen as `DrainOnly`: no accrual, trades or liquidations, only
+    /// `close_resolved_asset_leg_not_atomic` until no legs remain.
+    pub fn resolve_asset_not_atomic(
+        &mut self,
+        asset_index: usize,
+        now_slot: u64,
+        price: ResolveAssetPriceV16,
+    ) -> V16Result<()> {
+        self.validate_configured_asset_index(asset_index)?;
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::ResolveSlotInPast)));
+        }
+        let asset = self.asset_state(asset_index)?;
+        if asset.resolved_slot != 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetResolved))
+            );
+        }
+        let bump_asset_set = match asset.lifecycle {
+            AssetLifecycleV16::Active => true,
+            AssetLifecycleV16::DrainOnly => false,
+            _ => {
+                return Err(self
+                    .reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetNotResolvable)));
+            }
+        };
+        self.resolve_asset_price_not_atomic(
+            asset_index,
+            ResolveModeV16::Ordinary,
+            now_slot,
+            price,
+            self.header.config.resolve_price_deviation_bps.get(),
+        )?;
+        let mut asset = self.asset_state(asset_index)?;
+        asset.lifecycle = AssetLifecycleV16::DrainOnly;
+        asset.resolved_slot = now_slot;
+        asset.resolved_price = price.resolved_price;
+        self.set_asset_state(asset_index, asset)?;
+        if bump_asset_set {
+            let (next_asset_set_epoch, next_risk_epoch) = self.checked_asset_set_epoch_bump()?;
+            self.commit_asset_set_epoch_bump(next_asset_set_epoch, next_risk_epoch);
+        }
+        self.header.current_slot = V16PodU64::new(now_slot);
+        self.validate_shape()
+    }
+
+    /// Closes an account's leg in a resolved asset. The terminal K/F move is realized into PnL
+    /// first, so gains are credited (and later paid out through the usual haircut conversion)
+    /// rather than forfeited; losses then follow the dead-leg path. The leg is detached once the
+    /// account is non-negative with no open close ledger.
+    pub fn close_resolved_asset_leg_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        asset_index: usize,
+        b_delta_budget: u128,
+    ) -> V16Result<DeadLegForfeitOutcomeV16> {
+        account.validate_with_market(&self.as_view())?;
+        self.validate_configured_asset_index(asset_index)?;
+        if b_delta_budget == 0 {
+            return Err(self.reject(
+                V16Error::InvalidLeg.with_reason(V16ErrorReason::ResolvedCloseZeroBudget),
+            ));
+        }
+        if self.asset_state(asset_index)?.resolved_slot == 0 {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::AssetNotResolved))
+            );
+        }
+        let leg_slot = Self::require_active_leg_slot_for_asset(&account.as_view(), asset_index)?;
+        self.settle_leg_kf_effects_at_slot(account, leg_slot)?;
+        let leg = account.header.legs[leg_slot].try_to_runtime()?;
+        self.detach_terminal_leg_not_atomic(account, asset_index, leg, b_delta_budget)
+    }
+
+    /// Runs the checks every config update must pass against the live group: public-fund
+    /// validation of the candidate config, every asset risk profile as it would stand after the
+    /// update, and the portfolio-margin offset table. Returns the candidate config.
+    fn validate_config_update(&self, update: ConfigUpdateV16) -> V16Result<V16Config> {
+        let candidate = update.apply_to(self.header.config.try_to_runtime_shape()?);
+        candidate.validate_public_user_fund().map_err(|error| {
+            self.reject(error.with_reason(V16ErrorReason::ConfigUpdateInvalidConfig))
+        })?;
+        if let Some(profile_update) = update.asset_risk_profile {
+            let asset_index = profile_update.asset_index as usize;
+            self.validate_configured_asset_index(asset_index)?;
+            if !matches!(
+                self.asset_state(asset_index)?.lifecycle,
+                AssetLifecycleV16::Active | AssetLifecycleV16::DrainOnly
+            ) {
+                return Err(self.reject(
+                    V16Error::LockActive.with_reason(V16ErrorReason::ConfigUpdateAssetNotActive),
+                ));
+            }
+        }
+        let mut i = 0usize;
+        while i < candidate.max_market_slots as usize && i < self.markets.len() {
+            let profile = match update.asset_risk_profile {
+                Some(profile_update) if profile_update.asset_index as usize == i => {
+                    profile_update.profile
+                }
+                _ => self.markets[i].engine.risk_profile.try_to_runtime()?,
+            };
+            candidate
+                .validate_asset_risk_profile(profile)
+                .map_err(|error| {
+                    self.reject(error.with_reason(V16ErrorReason::ConfigUpdateInvalidRiskProfile))
+                })?;
+            i += 1;
+        }
+        let offsets = match update.portfolio_margin_offsets {
+            Some(offsets) => offsets,
+            None => self.portfolio_margin_offset_table()?,
+        };
+        self.validate_portfolio_margin_offsets(candidate, update.asset_risk_profile, &offsets)?;
+        Ok(candidate)
+    }
+
+    /// True when `update` can turn a currently certified account unhealthy: the group config
+    /// tightens (`ConfigUpdateV16::tightens_margin`), some asset's effective risk config does, or
+    /// the offset table removes or shrinks a pair's credit.
+    fn config_update_tightens_margin(&self, update: ConfigUpdateV16) -> V16Result<bool> {
+        let config = self.header.config.try_to_runtime_shape()?;
+        if update.tightens_margin(&config) {
+            return Ok(true);
+        }
+        if let Some(offsets) = update.portfolio_margin_offsets {
+            let current = self.portfolio_margin_offset_table()?;
+            let mut i = 0usize;
+            while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+                if current[i].offset_bps != 0 {
+                    let mut kept_bps = 0u64;
+                    let mut j = 0usize;
+                    while j < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+                        if offsets[j].offset_bps != 0
+                            && offsets[j].asset_a == current[i].asset_a
+                            && offsets[j].asset_b == current[i].asset_b
+                        {
+                            kept_bps = offsets[j].offset_bps;
+                        }
+                        j += 1;
+                    }
+                    if kept_bps < current[i].offset_bps {
+                        return Ok(true);
+                    }
+                }
+                i += 1;
+            }
+        }
+        let candidate = update.apply_to(config);
+        let mut i = 0usize;
+        while i < config.max_market_slots as usize && i < self.markets.len() {
+            if self
+                .asset_risk_config_after(candidate, update.asset_risk_profile, i)?
+                .tightens_margin_from(&self.asset_risk_config(config, i)?)
+            {
+                return Ok(true);
+            }
+            i += 1;
+        }
+        Ok(false)
+    }
+
+    /// Queues a governance config update. It must pass `validate_config_update` now and again
+    /// at activation; it becomes activatable `config_update_timelock_slots` after
+    /// `now_slot`, plus `config_update_grace_slots` when it tightens margin. Only one update may
+    /// be pending; cancel it to propose another.
+    pub fn propose_config_update_not_atomic(
+        &mut self,
+        update: ConfigUpdateV16,
+        now_slot: u64,
+    ) -> V16Result<u64> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::SlotBehindMarket)));
+        }
+        if self
+            .header
+            .pending_config_update
+            .try_to_runtime()?
+            .is_some()
+        {
+            return Err(self.reject(
+                V16Error::LockActive.with_reason(V16ErrorReason::ConfigUpdateAlreadyPending),
+            ));
+        }
+        let config = self.header.config.try_to_runtime_shape()?;
+        self.validate_config_update(update)?;
+        let mut delay = config.config_update_timelock_slots;
+        if self.config_update_tightens_margin(update)? {
+            delay = delay
+                .checked_add(config.config_update_grace_slots)
+                .ok_or(V16Error::ArithmeticOverflow)?;
+        }
+        let activation_slot = now_slot
+            .checked_add(delay)
+            .ok_or(V16Error::ArithmeticOverflow)?;
+        self.header.pending_config_update =
+            PendingConfigUpdateV16Account::from_runtime(Some(PendingConfigUpdateV16 {
+                update,
+                proposed_slot: now_slot,
+                activation_slot,
+            }));
+        Ok(activation_slot)
+    }
+
+    /// Drops the pending config update without applying it, freeing the slot for a new proposal.
+    pub fn cancel_config_update_not_atomic(&mut self) -> V16Result<()> {
+        if self
+            .header
+            .pending_config_update
+            .try_to_runtime()?
+            .is_none()
+        {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ConfigUpdateNotPending),
+            ));
+        }
+        self.header.pending_config_update = PendingConfigUpdateV16Account::from_runtime(None);
+        Ok(())
+    }
+
+    /// Activates the pending config update once its timelock has passed. The candidate is
+    /// revalidated against the config as it stands now, every enabled asset risk profile must
+    /// still validate under it, and the risk epoch is bumped so certificates are recomputed.
+    pub fn activate_config_update_not_atomic(&mut self, now_slot: u64) -> V16Result<()> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::SlotBehindMarket)));
+        }
+        let Some(pending) = self.header.pending_config_update.try_to_runtime()? else {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ConfigUpdateNotPending),
+            ));
+        };
+        if now_slot < pending.activation_slot {
+            return Err(self
+                .reject(V16Error::LockActive.with_reason(V16ErrorReason::ConfigUpdateTimelocked)));
+        }
+        let candidate = self.validate_config_update(pending.update)?;
+        let next_risk_epoch = self
+            .header
+            .risk_epoch
+            .get()
+            .checked_add(1)
+            .ok_or(V16Error::CounterOverflow)?;
+        self.header.config = V16ConfigAccount::from_runtime(&candidate);
+        if let Some(profile_update) = pending.update.asset_risk_profile {
+            self.markets[profile_update.asset_index as usize]
+                .engine_slot_mut()
+                .risk_profile = AssetRiskProfileV16Account::from_runtime(&profile_update.profile);
+        }
+        if let Some(offsets) = pending.update.portfolio_margin_offsets {
+            let mut i = 0usize;
+            while i < V16_MAX_PORTFOLIO_MARGIN_OFFSETS {
+                self.header.portfolio_margin_offsets[i] =
+                    PortfolioMarginOffsetV16Account::from_runtime(&offsets[i]);
+                i += 1;
+            }
         }
+        self.header.pending_config_update = PendingConfigUpdateV16Account::from_runtime(None);
+        self.header.risk_epoch = V16PodU64::new(next_risk_epoch);
+        self.header.current_slot = V16PodU64::new(now_slot);
+        self.validate_shape()
+    }
 
+    /// Moves the account's leg on `asset_index` into isolated margin with `allocated_capital`
+    /// earmarked for it, or back to the shared pool when `allocated_capital` is zero. The leg
+    /// is settled first and a new allocation restarts its settled PnL. The allocation must cover
+    /// the leg's initial requirement, all allocations together may not exceed the account's
+    /// capital, and the remaining pool must still cover the cross legs' initial requirement.
+    pub fn set_isolated_margin_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        asset_index: usize,
+        allocated_capital: u128,
+    ) -> V16Result<HealthCertV16> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        self.validate_configured_asset_index(asset_index)?;
+        let leg_slot = Self::require_active_leg_slot_for_asset(&account.as_view(), asset_index)?;
+        self.full_account_refresh_not_atomic(account)?;
+        let isolated = if allocated_capital == 0 {
+            IsolatedMarginV16::EMPTY
+        } else {
+            IsolatedMarginV16 {
+                enabled: true,
+                allocated_capital,
+                settled_pnl: 0,
+            }
+        };
+        let mut allocated_total = allocated_capital;
         let mut slot = 0usize;
         while slot < V16_MAX_PORTFOLIO_ASSETS_N {
-            let leg = account.header.legs[slot].try_to_runtime()?;
-            if leg.active {
-                self.clear_leg(account, leg.asset_index as usize)?;
+            if slot != leg_slot {
+                allocated_total = allocated_total
+                    .checked_add(
+                        account.header.isolated_margin[slot]
+                            .try_to_runtime()?
+                            .allocated_capital,
+                    )
+                    .ok_or(V16Error::ArithmeticOverflow)?;
             }
             slot += 1;
         }
-        Ok(())
+        if allocated_total > account.header.capital.get() {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::IsolatedMarginExceedsCapital),
+            ));
+        }
+        if isolated.enabled {
+            let (leg_initial, _) = self.leg_margin_requirements(&account.as_view(), leg_slot)?;
+            if allocated_capital < leg_initial {
+                return Err(self.reject(
+                    V16Error::InvalidConfig.with_reason(V16ErrorReason::IsolatedMarginBelowInitial),
+                ));
+            }
+        }
+        account.header.isolated_margin[leg_slot] =
+            IsolatedMarginV16Account::from_runtime(&isolated);
+        account.header.health_cert.valid = 0;
+        let cert = self.full_account_refresh_not_atomic(account)?;
+        Self::ensure_initial_margin(&account.as_view()).map_err(|r| self.reject(r))?;
+        Ok(cert)
     }
 
-    pub fn deposit_not_atomic(
+    /// Sets the owner's own initial margin rate on the account's gross cross notional (zero
+    /// clears it); isolated legs are already held to their whole allocation and are excluded.
+    /// A stricter rate applies at once; a looser one is parked until `now_slot` plus
+    /// `config_update_timelock_slots` and applied by
+    /// `activate_account_initial_margin_bps_not_atomic`. Returns the slot it takes effect.
+    pub fn set_account_initial_margin_bps_not_atomic(
         &mut self,
         account: &mut PortfolioV16ViewMut<'_>,
-        amount: u128,
-    ) -> V16Result<()> {
+        initial_margin_bps: u64,
+        now_slot: u64,
+    ) -> V16Result<u64> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::SlotBehindMarket)));
+        }
+        if initial_margin_bps > MAX_MARGIN_BPS {
+            return Err(self.reject(
+                V16Error::InvalidConfig
+                    .with_reason(V16ErrorReason::AccountInitialMarginOutOfBounds),
+            ));
+        }
         account.validate_with_market(&self.as_view())?;
-        if amount == 0 {
-            return Ok(());
+        let current = account.header.account_initial_margin_bps.get();
+        if current == 0 || (initial_margin_bps != 0 && initial_margin_bps >= current) {
+            account.header.account_initial_margin_bps = V16PodU64::new(initial_margin_bps);
+            account.header.pending_account_initial_margin_bps = V16PodU64::new(0);
+            account.header.pending_account_initial_margin_slot = V16PodU64::new(0);
+            account.header.health_cert.valid = 0;
+            return Ok(now_slot);
         }
-        let vault_before = self.header.vault.get();
-        let capital = account
-            .header
-            .capital
-            .get()
-            .checked_add(amount)
-            .ok_or(V16Error::ArithmeticOverflow)?;
-        let c_tot = self
-            .header
-            .c_tot
-            .get()
-            .checked_add(amount)
-            .ok_or(V16Error::ArithmeticOverflow)?;
-        let vault = self
-            .header
-            .vault
-            .get()
-            .checked_add(amount)
+        let activation_slot = now_slot
+            .checked_add(
+                self.header
+                    .config
+                    .try_to_runtime_shape()?
+                    .config_update_timelock_slots,
+            )
             .ok_or(V16Error::ArithmeticOverflow)?;
-        TokenValueFlowProofV16::external_in_to_account_capital(amount, vault_before, vault)?
-            .validate()?;
-        account.header.capital = V16PodU128::new(capital);
-        account.header.health_cert.valid = 0;
-        self.header.c_tot = V16PodU128::new(c_tot);
-        self.header.vault = V16PodU128::new(vault);
+        account.header.pending_account_initial_margin_bps = V16PodU64::new(initial_margin_bps);
+        account.header.pending_account_initial_margin_slot = V16PodU64::new(activation_slot);
+        Ok(activation_slot)
+    }
+
+    /// Applies a looser account initial margin rate parked by
+    /// `set_account_initial_margin_bps_not_atomic` once its timelock has passed.
+    pub fn activate_account_initial_margin_bps_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        now_slot: u64,
+    ) -> V16Result<()> {
+        if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
+        }
+        if now_slot < self.header.current_slot.get() {
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::SlotBehindMarket)));
+        }
         account.validate_with_market(&self.as_view())?;
-        self.validate_shape()
+        let activation_slot = account.header.pending_account_initial_margin_slot.get();
+        if activation_slot == 0 {
+            return Err(self.reject(
+                V16Error::InvalidConfig.with_reason(V16ErrorReason::ConfigUpdateNotPending),
+            ));
+        }
+        if now_slot < activation_slot {
+            return Err(self
+                .reject(V16Error::LockActive.with_reason(V16ErrorReason::ConfigUpdateTimelocked)));
+        }
+        account.header.account_initial_margin_bps =
+            account.header.pending_account_initial_margin_bps;
+        account.header.pending_account_initial_margin_bps = V16PodU64::new(0);
+        account.header.pending_account_initial_margin_slot = V16PodU64::new(0);
+        account.header.health_cert.valid = 0;
+        Ok(())
     }
 
     pub fn mark_asset_drain_only_not_atomic(&mut self, asset_index: usize) -> V16Result<()> {
         self.validate_configured_asset_index(asset_index)?;
         if decode_market_mode(self.header.mode)? != MarketModeV16::Live {
-            return Err(V16Error::LockActive);
+            return Err(
+                self.reject(V16Error::LockActive.with_reason(V16ErrorReason::MarketNotLive))
+            );
         }
         let mut asset = self.asset_state(asset_index)?;
         match asset.lifecycle {
@@ -14450,7 +19031,7 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     ) -> V16Result<()> {
         self.validate_configured_asset_index(asset_index)?;
         if now_slot < self.header.current_slot.get() {
-            return Err(V16Error::Stale);
+            return Err(self.reject(V16Error::Stale.with_reason(V16ErrorReason::SlotBehindMarket)));
         }
         let mut asset = self.asset_state(asset_index)?;
         match asset.lifecycle {
@@ -14689,7 +19270,19 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
         if !self.leg_is_dead_for_forfeit(asset_index, leg.side)? {
             return Err(V16Error::LockActive);
         }
+        self.detach_terminal_leg_not_atomic(account, asset_index, leg, b_delta_budget)
+    }
 
+    /// Detaches a leg whose asset can no longer move: any K/F gain still unsettled on the leg is
+    /// forfeited, losses run through principal, domain insurance and bankruptcy booking, and the
+    /// leg is cleared once the account is non-negative with no open close ledger.
+    fn detach_terminal_leg_not_atomic(
+        &mut self,
+        account: &mut PortfolioV16ViewMut<'_>,
+        asset_index: usize,
+        leg: PortfolioLegV16,
+        b_delta_budget: u128,
+    ) -> V16Result<DeadLegForfeitOutcomeV16> {
         let (k_target, f_target) = self.kf_target_for_leg(asset_index, leg)?;
         let b_target = self.b_target_for_leg(asset_index, leg)?;
         if account.header.pnl.get() == 0
@@ -14834,6 +19427,249 @@ impl<'a, T> MarketGroupV16ViewMut<'a, T> {
     }
 }
 
+/// All-or-nothing wrapper over the `_not_atomic` mutators for hosts that do not get SVM
+/// rollback for free (keepers, simulators, non-Solana runtimes).
+///
+/// The transaction journals the group header and every supplied portfolio when it begins, and
+/// journals an engine asset slot the first time an operation writes it: each operation runs
+/// against a scratch copy of the not-yet-journaled slots, and any slot the operation changed is
+/// journaled with its pre-operation bytes, whether or not the caller expected the write.
+/// Wrapper-owned `Market<T>::wrapper` bytes are never written by the engine and are not
+/// journaled. Any `V16Error` from an operation restores the journaled bytes and aborts the
+/// transaction; dropping an uncommitted transaction rolls it back as well.
+///
+/// Events are not journaled: a sink attached to the wrapped view has already seen whatever the
+/// aborted operation emitted before it failed, and must drop those events itself.
+pub struct MarketGroupV16Transaction<'t, 'a, 'p, T> {
+    market: &'t mut MarketGroupV16ViewMut<'a, T>,
+    portfolios: &'t mut [PortfolioV16ViewMut<'p>],
+    header_before: MarketGroupV16HeaderAccount,
+    portfolios_before: Vec<PortfolioAccountV16Account>,
+    slots_before: Vec<(usize, EngineAssetSlotV16Account)>,
+    finished: bool,
+}
+
+impl<'t, 'a, 'p, T> MarketGroupV16Transaction<'t, 'a, 'p, T> {
+    pub fn begin(
+        market: &'t mut MarketGroupV16ViewMut<'a, T>,
+        portfolios: &'t mut [PortfolioV16ViewMut<'p>],
+    ) -> V16Result<Self> {
+        let header_before = *market.header;
+        let mut portfolios_before = Vec::with_capacity(portfolios.len());
+        let mut i = 0usize;
+        while i < portfolios.len() {
+            portfolios_before.push(*portfolios[i].header);
+            i += 1;
+        }
+        Ok(Self {
+            market,
+            portfolios,
+            header_before,
+            portfolios_before,
+            slots_before: Vec::new(),
+            finished: false,
+        })
+    }
+
+    pub fn market(&self) -> MarketGroupV16View<'_, T> {
+        self.market.as_view()
+    }
+
+    pub fn portfolio(&self, index: usize) -> V16Result<PortfolioV16View<'_>> {
+        self.portfolios
+            .get(index)
+            .map(PortfolioV16ViewMut::as_view)
+            .ok_or(V16Error::InvalidConfig)
+    }
+
+    pub fn journaled_asset_count(&self) -> usize {
+        self.slots_before.len()
+    }
+
+    fn is_journaled(&self, asset_index: usize) -> bool {
+        let mut i = 0usize;
+        while i < self.slots_before.len() {
+            if self.slots_before[i].0 == asset_index {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// Pre-operation bytes of every engine slot not yet in the journal.
+    fn unjournaled_slots(&self) -> Vec<(usize, EngineAssetSlotV16Account)> {
+        let mut scratch = Vec::new();
+        let mut asset_index = 0usize;
+        while asset_index < self.market.markets.len() {
+            if !self.is_journaled(asset_index) {
+                scratch.push((asset_index, self.market.markets[asset_index].engine));
+            }
+            asset_index += 1;
+        }
+        scratch
+    }
+
+    /// Journals every scratch slot the operation changed, keeping its pre-operation bytes.
+    fn journal_written_slots(&mut self, scratch: Vec<(usize, EngineAssetSlotV16Account)>) {
+        let mut i = 0usize;
+        while i < scratch.len() {
+            let (asset_index, before) = scratch[i];
+            if self.market.markets[asset_index].engine != before {
+                self.slots_before.push((asset_index, before));
+            }
+            i += 1;
+        }
+    }
+
+    fn restore(&mut self) {
+        *self.market.header = self.header_before;
+        let mut i = 0usize;
+        while i < self.slots_before.len() {
+            let (asset_index, slot) = self.slots_before[i];
+            self.market.markets[asset_index].engine = slot;
+            i += 1;
+        }
+        let mut p = 0usize;
+        while p < self.portfolios.len() {
+            *self.portfolios[p].header = self.portfolios_before[p];
+            p += 1;
+        }
+    }
+
+    /// Runs one engine operation inside the transaction; every engine slot it writes is journaled.
+    pub fn apply<R, F>(&mut self, op: F) -> V16Result<R>
+    where
+        F: FnOnce(
+            &mut MarketGroupV16ViewMut<'a, T>,
+            &mut [PortfolioV16ViewMut<'p>],
+        ) -> V16Result<R>,
+    {
+        if self.finished {
+            return Err(V16Error::LockActive);
+        }
+        let scratch = self.unjournaled_slots();
+        let result = op(self.market, self.portfolios);
+        self.journal_written_slots(scratch);
+        match result {
+            Ok(out) => Ok(out),
+            Err(err) => {
+                self.restore();
+                self.finished = true;
+                Err(err)
+            }
+        }
+    }
+
+    fn portfolio_pair<'s>(
+        portfolios: &'s mut [PortfolioV16ViewMut<'p>],
+        first: usize,
+        second: usize,
+    ) -> V16Result<(
+        &'s mut PortfolioV16ViewMut<'p>,
+        &'s mut PortfolioV16ViewMut<'p>,
+    )> {
+        if first == second || first >= portfolios.len() || second >= portfolios.len() {
+            return Err(V16Error::InvalidConfig);
+        }
+        if first < second {
+            let (lo, hi) = portfolios.split_at_mut(second);
+            Ok((&mut lo[first], &mut hi[0]))
+        } else {
+            let (lo, hi) = portfolios.split_at_mut(first);
+            Ok((&mut hi[0], &mut lo[second]))
+        }
+    }
+
+    pub fn deposit(&mut self, account: usize, amount: u128) -> V16Result<()> {
+        self.apply(|market, portfolios| {
+            let account = portfolios.get_mut(account).ok_or(V16Error::InvalidConfig)?;
+            market.deposit_not_atomic(account, amount)
+        })
+    }
+
+    pub fn withdraw(&mut self, account: usize, amount: u128) -> V16Result<()> {
+        self.apply(|market, portfolios| {
+            let account = portfolios.get_mut(account).ok_or(V16Error::InvalidConfig)?;
+            market.withdraw_not_atomic(account, amount)
+        })
+    }
+
+    pub fn accrue_asset_to(
+        &mut self,
+        asset_index: usize,
+        now_slot: u64,
+        effective_price: u64,
+        funding_rate_e9: i128,
+        protective_progress_committed: bool,
+    ) -> V16Result<AccrueAssetOutcomeV16> {
+        self.apply(|market, _| {
+            market.accrue_asset_to_not_atomic(
+                asset_index,
+                now_slot,
+                effective_price,
+                funding_rate_e9,
+                protective_progress_committed,
+            )
+        })
+    }
+
+    pub fn execute_batch_with_fee_loss_stale_scoped(
+        &mut self,
+        long_account: usize,
+        short_account: usize,
+        requests: &[TradeRequestV16],
+    ) -> V16Result<BatchTradeOutcomeV16> {
+        self.apply(|market, portfolios| {
+            let (long, short) = Self::portfolio_pair(portfolios, long_account, short_account)?;
+            market.execute_batch_with_fee_loss_stale_scoped_not_atomic(long, short, requests)
+        })
+    }
+
+    pub fn liquidate_account(
+        &mut self,
+        account: usize,
+        request: LiquidationRequestV16,
+    ) -> V16Result<LiquidationOutcomeV16> {
+        self.apply(|market, portfolios| {
+            let account = portfolios.get_mut(account).ok_or(V16Error::InvalidConfig)?;
+            market.liquidate_account_not_atomic(account, request)
+        })
+    }
+
+    pub fn permissionless_crank(
+        &mut self,
+        account: usize,
+        request: PermissionlessCrankRequestV16,
+    ) -> V16Result<PermissionlessProgressOutcomeV16> {
+        self.apply(|market, portfolios| {
+            let account = portfolios.get_mut(account).ok_or(V16Error::InvalidConfig)?;
+            market.permissionless_crank_not_atomic(account, request)
+        })
+    }
+
+    /// Keeps every operation applied so far and releases the journal.
+    pub fn commit(mut self) {
+        self.finished = true;
+    }
+
+    /// Restores the journaled bytes. A no-op if an operation already aborted the transaction.
+    pub fn rollback(mut self) {
+        if !self.finished {
+            self.restore();
+            self.finished = true;
+        }
+    }
+}
+
+impl<'t, 'a, 'p, T> Drop for MarketGroupV16Transaction<'t, 'a, 'p, T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.restore();
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct PortfolioLegV16Account {
@@ -14902,6 +19738,37 @@ impl PortfolioLegV16Account {
     }
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct IsolatedMarginV16Account {
+    pub enabled: u8,
+    pub allocated_capital: V16PodU128,
+    pub settled_pnl: V16PodI128,
+}
+
+impl IsolatedMarginV16Account {
+    pub fn from_runtime(value: &IsolatedMarginV16) -> Self {
+        Self {
+            enabled: encode_bool(value.enabled),
+            allocated_capital: V16PodU128::new(value.allocated_capital),
+            settled_pnl: V16PodI128::new(value.settled_pnl),
+        }
+    }
+
+    pub fn try_to_runtime(&self) -> V16Result<IsolatedMarginV16> {
+        let out = IsolatedMarginV16 {
+            enabled: decode_bool(self.enabled)?,
+            allocated_capital: self.allocated_capital.get(),
+            settled_pnl: self.settled_pnl.get(),
+        };
+        validate_non_min_i128(out.settled_pnl)?;
+        if !out.enabled && out != IsolatedMarginV16::EMPTY {
+            return Err(V16Error::InvalidLeg);
+        }
+        Ok(out)
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct HealthCertV16Account {
@@ -15105,6 +19972,44 @@ impl ResolvedPayoutReceiptV16Account {
     }
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct WarmupReserveV16Account {
+    pub sched_start_slot: V16PodU64,
+    pub sched_horizon: V16PodU64,
+    pub sched_anchor: V16PodU128,
+    pub sched_remaining: V16PodU128,
+    pub sched_released: V16PodU128,
+    pub pending_remaining: V16PodU128,
+    pub pending_horizon: V16PodU64,
+}
+
+impl WarmupReserveV16Account {
+    pub fn from_runtime(value: &WarmupReserveV16) -> Self {
+        Self {
+            sched_start_slot: V16PodU64::new(value.sched_start_slot),
+            sched_horizon: V16PodU64::new(value.sched_horizon),
+            sched_anchor: V16PodU128::new(value.sched_anchor),
+            sched_remaining: V16PodU128::new(value.sched_remaining),
+            sched_released: V16PodU128::new(value.sched_released),
+            pending_remaining: V16PodU128::new(value.pending_remaining),
+            pending_horizon: V16PodU64::new(value.pending_horizon),
+        }
+    }
+
+    pub fn try_to_runtime(&self) -> V16Result<WarmupReserveV16> {
+        Ok(WarmupReserveV16 {
+            sched_start_slot: self.sched_start_slot.get(),
+            sched_horizon: self.sched_horizon.get(),
+            sched_anchor: self.sched_anchor.get(),
+            sched_remaining: self.sched_remaining.get(),
+            sched_released: self.sched_released.get(),
+            pending_remaining: self.pending_remaining.get(),
+            pending_horizon: self.pending_horizon.get(),
+        })
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct PortfolioSourceDomainV16Account {
@@ -15189,6 +20094,23 @@ pub struct PortfolioAccountV16Account {
     pub liquidation_lock: u8,
     pub close_progress: CloseProgressLedgerV16Account,
     pub resolved_payout_receipt: ResolvedPayoutReceiptV16Account,
+    pub warmup_reserve: WarmupReserveV16Account,
+    pub isolated_margin: [IsolatedMarginV16Account; V16_MAX_PORTFOLIO_ASSETS_N],
+    /// Slot the account was first certified liquidatable, zero while it is healthy.
+    pub liquidatable_since_slot: V16PodU64,
+    /// Owner-chosen initial margin on the account's gross notional, applied on top of the group
+    /// requirements; zero leaves the group's alone. A looser value waits in the pending pair.
+    pub account_initial_margin_bps: V16PodU64,
+    pub pending_account_initial_margin_bps: V16PodU64,
+    /// Slot from which the pending value may be activated, zero when none is pending.
+    pub pending_account_initial_margin_slot: V16PodU64,
+    /// Traded notional for fee tiers, decayed to `traded_notional_volume_slot`.
+    pub traded_notional_volume: V16PodU128,
+    pub traded_notional_volume_slot: V16PodU64,
+    /// Set while the account is in the host's materialized portfolio list; `materialized_index`
+    /// is its position there, which binds it to the keeper sweep cursor.
+    pub materialized: u8,
+    pub materialized_index: V16PodU64,
 }
 
 impl Default for PortfolioAccountV16Account {
@@ -15232,6 +20154,16 @@ impl PortfolioAccountV16Account {
         self.liquidation_lock = encode_bool(false);
         self.close_progress = CloseProgressLedgerV16Account::default();
         self.resolved_payout_receipt = ResolvedPayoutReceiptV16Account::default();
+        self.warmup_reserve = WarmupReserveV16Account::default();
+        self.isolated_margin = [IsolatedMarginV16Account::default(); V16_MAX_PORTFOLIO_ASSETS_N];
+        self.liquidatable_since_slot = V16PodU64::default();
+        self.account_initial_margin_bps = V16PodU64::default();
+        self.pending_account_initial_margin_bps = V16PodU64::default();
+        self.pending_account_initial_margin_slot = V16PodU64::default();
+        self.traded_notional_volume = V16PodU128::default();
+        self.traded_notional_volume_slot = V16PodU64::default();
+        self.materialized = encode_bool(false);
+        self.materialized_index = V16PodU64::default();
         Ok(())
     }
 
@@ -15321,16 +20253,37 @@ fn position_delta_increases_risk(current: i128, delta_q: i128) -> V16Result<bool
     Ok(next.unsigned_abs() > current.unsigned_abs())
 }
 
+/// A reduce-only fill may shrink or close the position but never grow it or flip its side.
+fn position_delta_is_reduce_only(current: i128, delta_q: i128) -> V16Result<bool> {
+    if position_delta_increases_risk(current, delta_q)? {
+        return Ok(false);
+    }
+    let next = current
+        .checked_add(delta_q)
+        .ok_or(V16Error::ArithmeticOverflow)?;
+    Ok(next == 0 || (next > 0) == (current > 0))
+}
+
 fn trade_preflight_risk_gate(
     risk_increasing: bool,
     asset_loss_stale: bool,
     target_effective_lag: bool,
     touches_pending_domain_barrier: bool,
-) -> V16Result<()> {
-    if touches_pending_domain_barrier
-        || (risk_increasing && (asset_loss_stale || target_effective_lag))
-    {
-        return Err(V16Error::LockActive);
+) -> Result<(), V16Rejection> {
+    if touches_pending_domain_barrier {
+        return Err(
+            V16Error::LockActive.with_reason(V16ErrorReason::TradeTouchesPendingLossBarrier)
+        );
+    }
+    if risk_increasing && asset_loss_stale {
+        return Err(
+            V16Error::LockActive.with_reason(V16ErrorReason::TradeRiskIncreaseWhileLossStale)
+        );
+    }
+    if risk_increasing && target_effective_lag {
+        return Err(
+            V16Error::LockActive.with_reason(V16ErrorReason::TradeRiskIncreaseWhileTargetLagged)
+        );
     }
     Ok(())
 }
@@ -15353,6 +20306,7 @@ pub fn kani_trade_preflight_risk_gate(
         target_effective_lag,
         touches_pending_domain_barrier,
     )
+    .map_err(V16Error::from)
 }
 
 fn margin_requirement(notional: u128, bps: u64, floor: u128) -> V16Result<u128> {
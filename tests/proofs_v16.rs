@@ -29,8 +29,9 @@ use percolator::v16::{
     SourceCreditStateV16Account, StockReconciliationProofV16, TokenValueClassV16,
     TokenValueFlowProofV16, V16Config, V16ConfigAccount, V16Error,
     V16OptionalRecoveryReasonAccount, V16PodI128, V16PodU128, V16PodU32, V16PodU64,
-    BACKING_FEE_RATE_DEN_E9, MAX_BACKING_FEE_RATE_E9_PER_SLOT, MAX_BACKING_FEE_UTIL_BPS,
-    PORTFOLIO_SOURCE_DOMAIN_CAP, V16_EMPTY_ACTIVE_BITMAP, V16_MAX_PORTFOLIO_ASSETS_N,
+    WarmupReserveV16, WarmupReserveV16Account, BACKING_FEE_RATE_DEN_E9,
+    MAX_BACKING_FEE_RATE_E9_PER_SLOT, MAX_BACKING_FEE_UTIL_BPS, PORTFOLIO_SOURCE_DOMAIN_CAP,
+    V16_EMPTY_ACTIVE_BITMAP, V16_MAX_PORTFOLIO_ASSETS_N,
 };
 use percolator::{
     ADL_ONE, BOUND_SCALE, CREDIT_RATE_SCALE, MAX_ACCOUNT_NOTIONAL, MAX_MARGIN_BPS,
@@ -42,6 +43,18 @@ fn ids() -> ([u8; 32], [u8; 32], [u8; 32]) {
     ([1; 32], [2; 32], [3; 32])
 }
 
+fn scheduled_warmup_reserve(reserved: u128) -> WarmupReserveV16Account {
+    if reserved == 0 {
+        return WarmupReserveV16Account::default();
+    }
+    WarmupReserveV16Account::from_runtime(&WarmupReserveV16 {
+        sched_horizon: 1,
+        sched_anchor: reserved,
+        sched_remaining: reserved,
+        ..WarmupReserveV16::EMPTY
+    })
+}
+
 fn empty_account_fixture(market_id: [u8; 32], account_tag: u8) -> PortfolioAccountV16Account {
     let mut account_id = [0u8; 32];
     account_id[0] = account_tag;
@@ -2624,6 +2637,7 @@ fn proof_v16_recovery_mode_blocks_fee_sync_and_pnl_conversion_before_mutation()
     account_header.capital = V16PodU128::new(capital);
     account_header.pnl = V16PodI128::new(pnl);
     account_header.reserved_pnl = V16PodU128::new(reserved);
+    account_header.warmup_reserve = scheduled_warmup_reserve(reserved);
     account_header.last_fee_slot = V16PodU64::new(0);
     let vault_before = header.vault;
     let c_tot_before = header.c_tot;
@@ -3194,6 +3208,8 @@ fn proof_v16_batch_outcome_accumulator_is_exact_and_overflow_checked() {
         fill_count,
         fee_a,
         fee_b,
+        rebate_a: 0,
+        rebate_b: 0,
         notional,
     };
     let mut risk = risk_before;
@@ -3324,6 +3340,8 @@ fn proof_v16_wrapper_shape_distinct_asset_batch_projection_preserves_oi_and_outc
         fill_count: 0,
         fee_a: 0,
         fee_b: 0,
+        rebate_a: 0,
+        rebate_b: 0,
         notional: 0,
     };
     let mut risk_increasing = false;
@@ -6212,6 +6230,7 @@ fn proof_v16_unliened_source_support_is_capped_by_realizable_backing() {
     let (mut header, mut markets, mut account_header) = one_market_view_fixture();
     account_header.pnl = V16PodI128::new(claim as i128);
     account_header.reserved_pnl = V16PodU128::new(claim);
+    account_header.warmup_reserve = scheduled_warmup_reserve(claim);
     account_header.source_domains[0].domain = V16PodU32::new(0);
     account_header.source_domains[0].source_claim_market_id = V16PodU64::new(1);
     account_header.source_domains[0].source_claim_bound_num = V16PodU128::new(claim_num);
@@ -11899,6 +11918,173 @@ fn proof_v16_credit_account_from_insurance_is_value_neutral_and_pool_isolated()
     assert_eq!(vault - (next_c_tot + next_insurance), residual_before);
 }
 
+#[kani::proof]
+#[kani::unwind(18)]
+#[kani::solver(cadical)]
+fn proof_v16_maker_rebate_never_leaves_insurance_below_its_pre_fill_level() {
+    let insurance_raw: u8 = kani::any();
+    let budget_raw: u8 = kani::any();
+    let c_tot_raw: u8 = kani::any();
+    let capital_raw: u8 = kani::any();
+    let fee_raw: u8 = kani::any();
+    let rebate_raw: u8 = kani::any();
+    kani::assume(insurance_raw <= 8);
+    kani::assume(budget_raw <= insurance_raw);
+    kani::assume(c_tot_raw <= 8);
+    kani::assume(capital_raw <= c_tot_raw);
+    kani::assume(fee_raw <= 8);
+    kani::assume(rebate_raw <= 9);
+    let insurance_before_fill = insurance_raw as u128;
+    let budget_remaining = budget_raw as u128;
+    let c_tot = c_tot_raw as u128;
+    let capital = capital_raw as u128;
+    let taker_fee = fee_raw as u128;
+    let rebate = rebate_raw as u128;
+    let insurance = insurance_before_fill + taker_fee;
+    let vault = c_tot + insurance;
+
+    let result = MarketGroupV16ViewMut::<u64>::kani_maker_rebate_delta(
+        insurance,
+        budget_remaining,
+        c_tot,
+        capital,
+        rebate,
+        taker_fee,
+    );
+
+    kani::cover!(
+        rebate > 0 && rebate == taker_fee,
+        "maker rebate may return the whole taker fee"
+    );
+    kani::cover!(
+        rebate > 0 && rebate < taker_fee,
+        "maker rebate below the taker fee leaves insurance better off"
+    );
+    kani::cover!(
+        rebate > taker_fee,
+        "maker rebate above the taker fee rejects"
+    );
+    if rebate > taker_fee {
+        assert_eq!(result, Err(V16Error::InvalidConfig));
+        return;
+    }
+    let (next_insurance, next_c_tot, next_capital) = result.unwrap();
+    assert!(next_insurance >= insurance_before_fill);
+    assert!(next_insurance >= budget_remaining);
+    assert_eq!(next_insurance, insurance - rebate);
+    assert_eq!(next_c_tot, c_tot + rebate);
+    assert_eq!(next_capital, capital + rebate);
+    assert_eq!(next_c_tot + next_insurance, vault);
+    let flow =
+        TokenValueFlowProofV16::insurance_capital_to_account_capital(rebate, vault, vault).unwrap();
+    assert_eq!(flow.validate(), Ok(()));
+}
+
+#[kani::proof]
+#[kani::unwind(18)]
+#[kani::solver(cadical)]
+fn proof_v16_liquidation_discount_moves_capital_between_accounts_without_vault_change() {
+    let account_raw: u8 = kani::any();
+    let liquidator_raw: u8 = kani::any();
+    let fee_raw: u8 = kani::any();
+    let shifted_vault: bool = kani::any();
+    let account_capital = account_raw as u128;
+    let liquidator_capital = liquidator_raw as u128;
+    let discount = (fee_raw as u128).min(account_capital);
+    let vault = account_capital + liquidator_capital;
+    let vault_after = if shifted_vault { vault + 1 } else { vault };
+
+    let flow =
+        TokenValueFlowProofV16::account_capital_to_account_capital(discount, vault, vault_after)
+            .unwrap();
+    let next_account_capital = account_capital - discount;
+    let next_liquidator_capital = liquidator_capital + discount;
+
+    kani::cover!(
+        !shifted_vault && discount > 0 && discount < account_capital,
+        "liquidation discount takes part of the liquidated account's capital"
+    );
+    kani::cover!(
+        shifted_vault && discount > 0,
+        "a discount that claims to move the vault rejects"
+    );
+    assert_eq!(next_account_capital + next_liquidator_capital, vault);
+    assert_eq!(
+        flow.debits[TokenValueClassV16::AccountCapital as usize],
+        discount
+    );
+    assert_eq!(
+        flow.credits[TokenValueClassV16::AccountCapital as usize],
+        discount
+    );
+    assert_eq!(flow.external_quote_in, 0);
+    assert_eq!(flow.external_quote_out, 0);
+    if shifted_vault {
+        assert_eq!(flow.validate(), Err(V16Error::InvalidConfig));
+    } else {
+        assert_eq!(flow.validate(), Ok(()));
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(18)]
+#[kani::solver(cadical)]
+fn proof_v16_funding_spread_sweep_takes_only_residual_above_junior_claims() {
+    let unswept_raw: u8 = kani::any();
+    let c_tot_raw: u8 = kani::any();
+    let insurance_raw: u8 = kani::any();
+    let residual_raw: u8 = kani::any();
+    let junior_raw: u8 = kani::any();
+    let unswept = unswept_raw as u128;
+    let c_tot = c_tot_raw as u128;
+    let insurance = insurance_raw as u128;
+    let residual = residual_raw as u128;
+    let junior_claim_bound = junior_raw as u128;
+    let vault = c_tot + insurance + residual;
+
+    let (swept, next_unswept, next_insurance) =
+        MarketGroupV16ViewMut::<u64>::kani_funding_spread_sweep_delta(
+            unswept,
+            residual,
+            junior_claim_bound,
+            insurance,
+        )
+        .unwrap();
+    let flow =
+        TokenValueFlowProofV16::protocol_surplus_to_insurance_capital(swept, vault, vault).unwrap();
+    let next_residual = vault - (c_tot + next_insurance);
+
+    kani::cover!(
+        swept > 0 && swept < unswept,
+        "sweep is capped by the residual above junior claims"
+    );
+    kani::cover!(
+        swept > 0 && swept == unswept && next_residual > junior_claim_bound,
+        "sweep takes the whole unswept spread when the residual allows it"
+    );
+    kani::cover!(
+        unswept > 0 && residual < junior_claim_bound,
+        "sweep takes nothing while junior claims exceed the residual"
+    );
+    assert_eq!(next_unswept + swept, unswept);
+    assert_eq!(next_insurance, insurance + swept);
+    assert_eq!(next_residual + swept, residual);
+    if residual >= junior_claim_bound {
+        assert!(next_residual >= junior_claim_bound);
+    } else {
+        assert_eq!(swept, 0);
+    }
+    assert_eq!(
+        flow.debits[TokenValueClassV16::UnallocatedProtocolSurplus as usize],
+        swept
+    );
+    assert_eq!(
+        flow.credits[TokenValueClassV16::InsuranceCapital as usize],
+        swept
+    );
+    assert_eq!(flow.validate(), Ok(()));
+}
+
 // reset_empty_asset_oracle_anchor re-anchors an EMPTY Active asset's oracle
 // price triple. It is gated on the whole group having NO position or loss
 // state, so re-anchoring can reprice nothing. The transition must move NO quote
@@ -158,12 +158,12 @@ fn apply_fuzz_action(
                 .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                     &mut long_account,
                     &mut short_account,
-                    TradeRequestV16 {
-                        asset_index: 0,
-                        size_q: i128::try_from(1 + (amount % 4)).unwrap(),
-                        exec_price: 1,
-                        fee_bps: (amount_seed as u64) % 11,
-                    },
+                    TradeRequestV16::new(
+                        0,
+                        i128::try_from(1 + (amount % 4)).unwrap(),
+                        1,
+                        (amount_seed as u64) % 11,
+                    ),
                 )
                 .map(|_| ())
         }
@@ -1,17 +1,27 @@
 use percolator::{
-    v16_domain_count_for_market_slots, AssetLifecycleV16, AssetStateV16Account,
-    BackingBucketStatusV16, BackingBucketV16, BackingBucketV16Account, EngineAssetSlotV16Account,
-    HealthCertV16, HealthCertV16Account, LiquidationRequestV16, Market,
-    MarketGroupV16HeaderAccount, MarketGroupV16ViewMut, PermissionlessCrankActionV16,
-    PermissionlessCrankRequestV16, PermissionlessProgressOutcomeV16,
+    v16_domain_count_for_market_slots, AssetLifecycleV16, AssetRiskProfileUpdateV16,
+    AssetRiskProfileV16, AssetStateV16Account, BackingBucketStatusV16, BackingBucketV16,
+    BackingBucketV16Account, ConfigUpdateV16, EngineAssetSlotV16Account, EngineEventV16,
+    EventSinkV16, FeeTierV16, HealthCertV16, HealthCertV16Account, IsolatedMarginV16,
+    KeeperCandidateV16, KeeperCrankOutcomeV16, KeeperCrankRequestV16, LiquidationRequestV16,
+    MarginBracketV16, Market, MarketGroupV16HeaderAccount, MarketGroupV16Transaction,
+    MarketGroupV16View, MarketGroupV16ViewMut, OracleCatchupOutcomeV16,
+    PermissionlessCrankActionV16, PermissionlessCrankRequestV16, PermissionlessProgressOutcomeV16,
     PermissionlessRecoveryReasonV16, PortfolioAccountV16Account, PortfolioLegV16,
-    PortfolioLegV16Account, PortfolioSourceDomainV16Account, PortfolioV16View, PortfolioV16ViewMut,
-    ProvenanceHeaderV16, ProvenanceHeaderV16Account, ResolvedPayoutLedgerV16,
+    PortfolioLegV16Account, PortfolioMarginOffsetV16, PortfolioSourceDomainV16Account,
+    PortfolioV16View, PortfolioV16ViewMut, ProvenanceHeaderV16, ProvenanceHeaderV16Account,
+    ResolveAssetPriceV16, ResolveModeV16, ResolvedCloseOutcomeV16, ResolvedPayoutLedgerV16,
     ResolvedPayoutLedgerV16Account, ResolvedPayoutReceiptV16, ResolvedPayoutReceiptV16Account,
-    SideModeV16, SideV16, SourceCreditStateV16, SourceCreditStateV16Account, TradeRequestV16,
-    V16Config, V16Error, V16PodI128, V16PodU128, V16PodU32, V16PodU64, V16_EMPTY_ACTIVE_BITMAP,
+    SideModeV16, SideV16, SourceCreditStateV16, SourceCreditStateV16Account, TradeMakerV16,
+    TradeRequestV16, V16Config, V16ConfigAccount, V16Error, V16ErrorReason, V16PodI128, V16PodU128,
+    V16PodU16, V16PodU32, V16PodU64, WarmupAdmissionV16, WarmupReserveV16,
+    DEFAULT_RESOLVE_PRICE_DEVIATION_BPS, V16_ACCOUNT_VERSION, V16_EMPTY_ACTIVE_BITMAP,
+    V16_MAX_PORTFOLIO_MARGIN_OFFSETS,
+};
+use percolator::{
+    ADL_ONE, BOUND_SCALE, CREDIT_RATE_SCALE, MAX_CONFIG_UPDATE_GRACE_SLOTS, POS_SCALE,
+    STRESS_CONSUMPTION_SCALE,
 };
-use percolator::{ADL_ONE, BOUND_SCALE, CREDIT_RATE_SCALE, POS_SCALE};
 
 fn ids() -> ([u8; 32], [u8; 32], [u8; 32]) {
     ([1; 32], [2; 32], [3; 32])
@@ -63,6 +73,33 @@ fn signed_q(q: u128) -> i128 {
     i128::try_from(q).unwrap()
 }
 
+/// Proposes `update` and activates it at its activation slot, with the group timelock cut to one
+/// slot and no grace period so fixtures can install governed risk settings.
+fn activate_config_update(
+    market: &mut MarketGroupV16ViewMut<'_, u64>,
+    update: ConfigUpdateV16,
+) -> Result<(), V16Error> {
+    market.header.config.config_update_timelock_slots = V16PodU64::new(1);
+    market.header.config.config_update_grace_slots = V16PodU64::new(0);
+    let activation_slot =
+        market.propose_config_update_not_atomic(update, market.header.current_slot.get())?;
+    market.activate_config_update_not_atomic(activation_slot)
+}
+
+fn asset_risk_profile_update(
+    market: &MarketGroupV16ViewMut<'_, u64>,
+    asset_index: u32,
+    profile: AssetRiskProfileV16,
+) -> ConfigUpdateV16 {
+    ConfigUpdateV16 {
+        asset_risk_profile: Some(AssetRiskProfileUpdateV16 {
+            asset_index,
+            profile,
+        }),
+        ..ConfigUpdateV16::from_config(&market.header.config.try_to_runtime_shape().unwrap())
+    }
+}
+
 #[test]
 fn v16_public_fund_validator_accepts_nontrivial_exact_solvency_profile() {
     let mut cfg = V16Config::public_user_fund_with_market_slots(1, 1, 1, 10);
@@ -146,12 +183,7 @@ fn v16_fee_sync_on_nonflat_account_settles_hidden_k_loss_before_fee() {
             .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                 &mut long,
                 &mut short,
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
             )
             .unwrap();
         market
@@ -179,6 +211,78 @@ fn v16_fee_sync_on_nonflat_account_settles_hidden_k_loss_before_fee() {
     long.validate_with_market(&market.as_view()).unwrap();
 }
 
+#[derive(Default)]
+struct RecordingSink {
+    events: Vec<EngineEventV16>,
+}
+
+impl EventSinkV16 for RecordingSink {
+    fn on_event(&mut self, event: EngineEventV16) {
+        self.events.push(event);
+    }
+}
+
+#[test]
+fn v16_event_sink_receives_fills_and_accrual_deltas() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 16);
+    let mut short_header = account_fixture(1, 17);
+    let mut sink = RecordingSink::default();
+    {
+        let mut market =
+            MarketGroupV16ViewMut::new(&mut header, &mut markets).with_event_sink(&mut sink);
+        let mut long = PortfolioV16ViewMut::new(&mut long_header);
+        let mut short = PortfolioV16ViewMut::new(&mut short_header);
+        market.deposit_not_atomic(&mut long, 1_000).unwrap();
+        market.deposit_not_atomic(&mut short, 1_000).unwrap();
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+            )
+            .unwrap();
+        // A fill the initial-margin check rejects is never reported.
+        assert_eq!(
+            market
+                .call_with_reason(|m| m.execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                    &mut long,
+                    &mut short,
+                    TradeRequestV16::new(0, signed_q(20 * POS_SCALE), 100, 0),
+                ))
+                .unwrap_err()
+                .reason,
+            V16ErrorReason::InitialMarginInsufficient
+        );
+        market
+            .accrue_asset_to_not_atomic(0, 2, 101, 0, true)
+            .unwrap();
+    }
+
+    let k_delta = i128::try_from(ADL_ONE).unwrap();
+    assert_eq!(
+        sink.events,
+        vec![
+            EngineEventV16::Fill {
+                asset_index: 0,
+                size_q: signed_q(POS_SCALE),
+                exec_price: 100,
+                notional: 100,
+                fee_long: 0,
+                fee_short: 0,
+            },
+            EngineEventV16::Accrual {
+                asset_index: 0,
+                dt: 1,
+                k_long_delta: k_delta,
+                k_short_delta: -k_delta,
+                f_long_num_delta: 0,
+                f_short_num_delta: 0,
+            },
+        ]
+    );
+}
+
 #[test]
 fn v16_finalize_side_reset_is_public_value_neutral_and_epoch_bumping() {
     let (mut header, mut markets) = market_fixture(1, 100);
@@ -230,7 +334,17 @@ fn v16_resolved_bound_refinement_uses_public_monotone_api() {
     let (mut header, mut markets) = market_fixture(1, 100);
     {
         let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
-        market.resolve_market_not_atomic(1).unwrap();
+        market
+            .resolve_market_with_prices_not_atomic(
+                ResolveModeV16::Degenerate,
+                1,
+                &[ResolveAssetPriceV16 {
+                    resolved_price: 100,
+                    live_oracle_price: 100,
+                    funding_rate_e9: 0,
+                }],
+            )
+            .unwrap();
     }
     header.vault = V16PodU128::new(50);
     let exact_num = 2 * BOUND_SCALE;
@@ -282,18 +396,8 @@ fn v16_batch_trade_applies_multiple_fills_after_inline_refresh() {
     let mut long_header = account_fixture(2, 201);
     let mut short_header = account_fixture(2, 202);
     let requests = [
-        TradeRequestV16 {
-            asset_index: 0,
-            size_q: signed_q(POS_SCALE),
-            exec_price: 100,
-            fee_bps: 0,
-        },
-        TradeRequestV16 {
-            asset_index: 1,
-            size_q: signed_q(2 * POS_SCALE),
-            exec_price: 100,
-            fee_bps: 0,
-        },
+        TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        TradeRequestV16::new(1, signed_q(2 * POS_SCALE), 100, 0),
     ];
 
     let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
@@ -333,6 +437,119 @@ fn v16_batch_trade_applies_multiple_fills_after_inline_refresh() {
     short.validate_with_market(&market.as_view()).unwrap();
 }
 
+#[test]
+fn v16_reduce_only_fill_cannot_grow_or_flip_the_flagged_side() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut holder_header = account_fixture(1, 211);
+    let mut short_header = account_fixture(1, 212);
+    let mut taker_header = account_fixture(1, 213);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut holder = PortfolioV16ViewMut::new(&mut holder_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    let mut taker = PortfolioV16ViewMut::new(&mut taker_header);
+    market.deposit_not_atomic(&mut holder, 10_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    market.deposit_not_atomic(&mut taker, 10_000).unwrap();
+    let open = TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 0);
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut holder, &mut short, open)
+        .unwrap();
+
+    // The taker buys from the holder: 15 units would flip the reduce-only holder short, and a
+    // reduce-only taker cannot open.
+    for request in [
+        TradeRequestV16 {
+            size_q: signed_q(15 * POS_SCALE),
+            reduce_only_b: true,
+            ..open
+        },
+        TradeRequestV16 {
+            size_q: signed_q(4 * POS_SCALE),
+            reduce_only_a: true,
+            ..open
+        },
+    ] {
+        assert_eq!(
+            market
+                .call_with_reason(|m| m.execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                    &mut taker,
+                    &mut holder,
+                    request,
+                ))
+                .map_err(|rejection| rejection.reason),
+            Err(V16ErrorReason::TradeReduceOnlyViolated)
+        );
+    }
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut taker,
+            &mut holder,
+            TradeRequestV16 {
+                size_q: signed_q(4 * POS_SCALE),
+                reduce_only_b: true,
+                ..open
+            },
+        )
+        .unwrap();
+    let leg = holder.header.legs[0].try_to_runtime().unwrap();
+    assert_eq!(
+        (leg.side, leg.basis_pos_q),
+        (SideV16::Long, signed_q(6 * POS_SCALE))
+    );
+}
+
+#[test]
+fn v16_post_only_fill_rejects_the_flagged_account_as_taker() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 214);
+    let mut short_header = account_fixture(1, 215);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 10_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    let fill = TradeRequestV16 {
+        post_only_b: true,
+        ..TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0)
+    };
+
+    for request in [
+        fill,
+        TradeRequestV16 {
+            maker: TradeMakerV16::A,
+            ..fill
+        },
+        TradeRequestV16 {
+            post_only_a: true,
+            maker: TradeMakerV16::B,
+            ..fill
+        },
+    ] {
+        assert_eq!(
+            market
+                .call_with_reason(|m| m.execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                    &mut long, &mut short, request,
+                ))
+                .map_err(|rejection| rejection.reason),
+            Err(V16ErrorReason::TradePostOnlyWouldTake)
+        );
+    }
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16 {
+                maker: TradeMakerV16::B,
+                ..fill
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        short.header.legs[0].try_to_runtime().unwrap().basis_pos_q,
+        -signed_q(POS_SCALE)
+    );
+}
+
 #[test]
 fn v16_batch_trade_supports_mixed_signed_spread_legs() {
     let (mut header, mut markets) = market_fixture(2, 100);
@@ -340,18 +557,8 @@ fn v16_batch_trade_supports_mixed_signed_spread_legs() {
     let mut lp_header = account_fixture(2, 222);
     let size_q = signed_q(5 * POS_SCALE);
     let requests = [
-        TradeRequestV16 {
-            asset_index: 0,
-            size_q,
-            exec_price: 100,
-            fee_bps: 0,
-        },
-        TradeRequestV16 {
-            asset_index: 1,
-            size_q: -size_q,
-            exec_price: 100,
-            fee_bps: 0,
-        },
+        TradeRequestV16::new(0, size_q, 100, 0),
+        TradeRequestV16::new(1, -size_q, 100, 0),
     ];
 
     let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
@@ -409,12 +616,7 @@ fn v16_single_trade_matches_batch_of_one_state() {
     let mut batch_markets = single_markets.clone();
     let mut batch_long_header = single_long_header;
     let mut batch_short_header = single_short_header;
-    let request = TradeRequestV16 {
-        asset_index: 0,
-        size_q: signed_q(2 * POS_SCALE),
-        exec_price: 100,
-        fee_bps: 0,
-    };
+    let request = TradeRequestV16::new(0, signed_q(2 * POS_SCALE), 100, 0);
 
     let single_outcome = {
         let mut market = MarketGroupV16ViewMut::new(&mut single_header, &mut single_markets);
@@ -462,12 +664,7 @@ fn v16_batch_trade_checks_initial_margin_on_final_portfolio() {
             .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                 &mut lp,
                 &mut taker,
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(10 * POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 0),
             )
             .unwrap();
     }
@@ -480,18 +677,8 @@ fn v16_batch_trade_checks_initial_margin_on_final_portfolio() {
             &mut taker,
             &mut lp,
             &[
-                TradeRequestV16 {
-                    asset_index: 1,
-                    size_q: signed_q(10 * POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(10 * POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(1, signed_q(10 * POS_SCALE), 100, 0),
+                TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 0),
             ],
         )
         .expect("batch must not reject a final-IM-valid basket due to interim IM");
@@ -530,6 +717,112 @@ fn v16_batch_trade_checks_initial_margin_on_final_portfolio() {
     lp.validate_with_market(&market.as_view()).unwrap();
 }
 
+#[test]
+fn v16_transaction_rolls_back_every_applied_operation_on_error() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut taker_header = account_fixture(2, 221);
+    let mut lp_header = account_fixture(2, 222);
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut taker = PortfolioV16ViewMut::new(&mut taker_header);
+        let mut lp = PortfolioV16ViewMut::new(&mut lp_header);
+        market.deposit_not_atomic(&mut taker, 1_000).unwrap();
+        market.deposit_not_atomic(&mut lp, 1_000).unwrap();
+    }
+    let header_before = header;
+    let markets_before = markets.clone();
+    let taker_before = taker_header;
+    let lp_before = lp_header;
+
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut portfolios = [
+            PortfolioV16ViewMut::new(&mut taker_header),
+            PortfolioV16ViewMut::new(&mut lp_header),
+        ];
+        let mut tx = MarketGroupV16Transaction::begin(&mut market, &mut portfolios).unwrap();
+        tx.deposit(0, 500).unwrap();
+        tx.execute_batch_with_fee_loss_stale_scoped(
+            0,
+            1,
+            &[TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 0)],
+        )
+        .unwrap();
+        assert_eq!(tx.journaled_asset_count(), 1);
+
+        assert_eq!(
+            tx.execute_batch_with_fee_loss_stale_scoped(
+                0,
+                1,
+                &[TradeRequestV16::new(1, signed_q(1_000 * POS_SCALE), 100, 0)],
+            ),
+            Err(V16Error::InvalidConfig),
+            "final IM failure aborts the whole transaction"
+        );
+        assert_eq!(tx.withdraw(0, 1), Err(V16Error::LockActive));
+    }
+
+    assert_eq!(header, header_before);
+    assert_eq!(markets, markets_before);
+    assert_eq!(taker_header, taker_before);
+    assert_eq!(lp_header, lp_before);
+}
+
+#[test]
+fn v16_transaction_commit_keeps_state_and_drop_rolls_back() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut account_header = account_fixture(1, 223);
+    let header_before = header;
+    let account_before = account_header;
+
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut portfolios = [PortfolioV16ViewMut::new(&mut account_header)];
+        let mut tx = MarketGroupV16Transaction::begin(&mut market, &mut portfolios).unwrap();
+        tx.deposit(0, 11).unwrap();
+        assert_eq!(tx.portfolio(0).unwrap().header.capital.get(), 11);
+    }
+    assert_eq!(header, header_before);
+    assert_eq!(account_header, account_before);
+
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut portfolios = [PortfolioV16ViewMut::new(&mut account_header)];
+        let mut tx = MarketGroupV16Transaction::begin(&mut market, &mut portfolios).unwrap();
+        tx.deposit(0, 11).unwrap();
+        tx.withdraw(0, 4).unwrap();
+        tx.commit();
+    }
+    assert_eq!(account_header.capital.get(), 7);
+    assert_eq!(header.c_tot.get(), 7);
+    assert_eq!(header.vault.get(), 7);
+}
+
+#[test]
+fn v16_transaction_journals_assets_the_operation_writes_without_naming_them() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut account_header = account_fixture(2, 224);
+    let markets_before = markets.clone();
+
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut portfolios = [PortfolioV16ViewMut::new(&mut account_header)];
+        let mut tx = MarketGroupV16Transaction::begin(&mut market, &mut portfolios).unwrap();
+        tx.apply(|market, _| market.set_asset_raw_oracle_target_not_atomic(1, 120))
+            .unwrap();
+        assert_eq!(tx.journaled_asset_count(), 1);
+        assert_eq!(
+            tx.apply(|market, _| {
+                market.set_asset_raw_oracle_target_not_atomic(0, 130)?;
+                market.set_asset_raw_oracle_target_not_atomic(1, 0)
+            }),
+            Err(V16Error::InvalidConfig)
+        );
+        assert_eq!(tx.journaled_asset_count(), 2);
+    }
+    assert_eq!(markets, markets_before);
+}
+
 #[test]
 fn v16_batch_trade_self_settles_stale_certificates_once_before_fills() {
     let (mut header, mut markets) = market_fixture(1, 100);
@@ -545,12 +838,7 @@ fn v16_batch_trade_self_settles_stale_certificates_once_before_fills() {
             .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                 &mut long,
                 &mut short,
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
             )
             .unwrap();
         market
@@ -567,12 +855,7 @@ fn v16_batch_trade_self_settles_stale_certificates_once_before_fills() {
         .execute_batch_with_fee_loss_stale_scoped_not_atomic(
             &mut long,
             &mut short,
-            &[TradeRequestV16 {
-                asset_index: 0,
-                size_q: signed_q(POS_SCALE),
-                exec_price: 101,
-                fee_bps: 0,
-            }],
+            &[TradeRequestV16::new(0, signed_q(POS_SCALE), 101, 0)],
         )
         .unwrap();
 
@@ -599,12 +882,7 @@ fn v16_batch_trade_rejects_loss_stale_risk_increase_after_inline_settlement() {
             .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                 &mut long,
                 &mut short,
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
             )
             .unwrap();
         market
@@ -619,12 +897,7 @@ fn v16_batch_trade_rejects_loss_stale_risk_increase_after_inline_settlement() {
     let res = market.execute_batch_with_fee_loss_stale_scoped_not_atomic(
         &mut long,
         &mut short,
-        &[TradeRequestV16 {
-            asset_index: 0,
-            size_q: signed_q(POS_SCALE),
-            exec_price: 101,
-            fee_bps: 0,
-        }],
+        &[TradeRequestV16::new(0, signed_q(POS_SCALE), 101, 0)],
     );
 
     assert_eq!(res, Err(V16Error::LockActive));
@@ -656,12 +929,7 @@ fn v16_public_scoped_trade_preserves_unrelated_loss_stale_summary() {
         .execute_trade_with_fee_loss_stale_scoped_not_atomic(
             &mut long,
             &mut short,
-            TradeRequestV16 {
-                asset_index: 0,
-                size_q: signed_q(POS_SCALE),
-                exec_price: 100,
-                fee_bps: 0,
-            },
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
         )
         .expect("unrelated loss-stale summary must not block a locally current trade");
 
@@ -679,18 +947,8 @@ fn v16_batch_trade_is_bounded_by_configured_portfolio_asset_cap() {
     let mut long_header = account_fixture(1, 205);
     let mut short_header = account_fixture(1, 206);
     let requests = [
-        TradeRequestV16 {
-            asset_index: 0,
-            size_q: signed_q(POS_SCALE),
-            exec_price: 100,
-            fee_bps: 0,
-        },
-        TradeRequestV16 {
-            asset_index: 0,
-            size_q: signed_q(POS_SCALE),
-            exec_price: 100,
-            fee_bps: 0,
-        },
+        TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
     ];
     let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
     let mut long = PortfolioV16ViewMut::new(&mut long_header);
@@ -997,6 +1255,84 @@ fn v16_retire_and_reactivate_empty_asset_after_source_credit_epoch_bump() {
     market.validate_shape().unwrap();
 }
 
+#[test]
+fn v16_simulated_batch_trade_matches_execution_without_mutating_inputs() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut taker_header = account_fixture(2, 231);
+    let mut lp_header = account_fixture(2, 232);
+    header.config.max_trading_fee_bps = V16PodU64::new(10);
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut taker = PortfolioV16ViewMut::new(&mut taker_header);
+        let mut lp = PortfolioV16ViewMut::new(&mut lp_header);
+        market.deposit_not_atomic(&mut taker, 1_000).unwrap();
+        market.deposit_not_atomic(&mut lp, 1_000).unwrap();
+    }
+    let requests = [
+        TradeRequestV16::new(0, signed_q(5 * POS_SCALE), 100, 10),
+        TradeRequestV16::new(1, -signed_q(2 * POS_SCALE), 100, 10),
+    ];
+    let header_before = header;
+    let markets_before = markets.clone();
+    let taker_before = taker_header;
+    let lp_before = lp_header;
+
+    let simulated = MarketGroupV16View::new(&header, &markets)
+        .simulate_execute_batch_with_fee_loss_stale_scoped(
+            &PortfolioV16View::new(&taker_header),
+            &PortfolioV16View::new(&lp_header),
+            &requests,
+        )
+        .unwrap();
+    assert_eq!(header, header_before);
+    assert_eq!(markets, markets_before);
+    assert_eq!(taker_header, taker_before);
+    assert_eq!(lp_header, lp_before);
+
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut taker = PortfolioV16ViewMut::new(&mut taker_header);
+    let mut lp = PortfolioV16ViewMut::new(&mut lp_header);
+    let outcome = market
+        .execute_batch_with_fee_loss_stale_scoped_not_atomic(&mut taker, &mut lp, &requests)
+        .unwrap();
+
+    assert_eq!(simulated.outcome, outcome);
+    assert_ne!(outcome.fee_a, 0);
+    assert_eq!(
+        simulated.long_account.health_cert,
+        taker.header.health_cert.try_to_runtime().unwrap()
+    );
+    assert_eq!(
+        simulated.short_account.health_cert,
+        lp.header.health_cert.try_to_runtime().unwrap()
+    );
+    assert_eq!(simulated.long_account.capital, taker.header.capital.get());
+    assert_eq!(simulated.short_account.pnl, lp.header.pnl.get());
+}
+
+#[test]
+fn v16_simulated_withdraw_reports_post_state_and_rejections() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut account_header = account_fixture(1, 233);
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut account = PortfolioV16ViewMut::new(&mut account_header);
+        market.deposit_not_atomic(&mut account, 3).unwrap();
+    }
+    let market = MarketGroupV16View::new(&header, &markets);
+    let account = PortfolioV16View::new(&account_header);
+
+    let simulated = market.simulate_withdraw(&account, 2).unwrap();
+    assert_eq!(simulated.account.capital, 1);
+    assert_eq!(simulated.account.health_cert.certified_equity, 1);
+    assert_eq!(
+        market.simulate_withdraw(&account, 4),
+        Err(V16Error::LockActive.with_reason(V16ErrorReason::WithdrawExceedsCapital))
+    );
+    assert_eq!(account.header.capital.get(), 3);
+    assert_eq!(market.header.vault.get(), 3);
+}
+
 #[test]
 fn v16_view_rejects_overwithdraw() {
     let (mut header, mut markets) = market_fixture(1, 100);
@@ -1012,20 +1348,76 @@ fn v16_view_rejects_overwithdraw() {
     assert_eq!(err, Err(V16Error::LockActive));
 }
 
-#[cfg(feature = "fuzz")]
 #[test]
-fn v16_insurance_lien_consume_rejects_fractional_bound_amount() {
+fn v16_account_with_previous_layout_version_is_rejected() {
     let (mut header, mut markets) = market_fixture(1, 100);
-    header.vault = V16PodU128::new(10);
-    header.insurance = V16PodU128::new(10);
-
+    let mut account_header = account_fixture(1, 8);
+    account_header.provenance_header.version = V16PodU16::new(V16_ACCOUNT_VERSION - 1);
     let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
-    market.deposit_domain_insurance_not_atomic(0, 10).unwrap();
-    market
-        .reserve_insurance_credit_not_atomic(0, BOUND_SCALE)
-        .unwrap();
-    market
-        .create_source_credit_lien_from_insurance_not_atomic(0, BOUND_SCALE)
+    let mut account = PortfolioV16ViewMut::new(&mut account_header);
+    assert_eq!(
+        market.deposit_not_atomic(&mut account, 1),
+        Err(V16Error::ProvenanceMismatch)
+    );
+}
+
+#[test]
+fn v16_withdraw_rejections_carry_distinct_reasons_within_category() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut account_header = account_fixture(1, 7);
+    let mut market_view = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut account_view = PortfolioV16ViewMut::new(&mut account_header);
+    market_view
+        .deposit_not_atomic(&mut account_view, 3)
+        .unwrap();
+
+    assert_eq!(
+        market_view.withdraw_not_atomic(&mut account_view, 4),
+        Err(V16Error::LockActive)
+    );
+    let over = market_view
+        .call_with_reason(|m| m.withdraw_not_atomic(&mut account_view, 4))
+        .unwrap_err();
+    assert_eq!(
+        over,
+        V16Error::LockActive.with_reason(V16ErrorReason::WithdrawExceedsCapital)
+    );
+    assert_eq!(over.code(), ((V16Error::LockActive as u32) << 16) | 103);
+
+    account_view.header.pnl = V16PodI128::new(-1);
+    account_view.header.capital = V16PodU128::new(0);
+    market_view.header.c_tot = V16PodU128::new(0);
+    market_view.header.insurance = V16PodU128::new(3);
+    market_view.header.negative_pnl_account_count = V16PodU64::new(1);
+    let loss = market_view
+        .call_with_reason(|m| m.withdraw_not_atomic(&mut account_view, 1))
+        .unwrap_err();
+    assert_eq!(
+        loss,
+        V16Error::LockActive.with_reason(V16ErrorReason::WithdrawUnsettledLoss)
+    );
+
+    // A shared range check reports its reason across operations.
+    assert_eq!(
+        market_view.call_with_reason(|m| m.set_asset_raw_oracle_target_not_atomic(3, 100)),
+        Err(V16Error::InvalidLeg.with_reason(V16ErrorReason::AssetIndexOutOfRange))
+    );
+}
+
+#[cfg(feature = "fuzz")]
+#[test]
+fn v16_insurance_lien_consume_rejects_fractional_bound_amount() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.vault = V16PodU128::new(10);
+    header.insurance = V16PodU128::new(10);
+
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    market.deposit_domain_insurance_not_atomic(0, 10).unwrap();
+    market
+        .reserve_insurance_credit_not_atomic(0, BOUND_SCALE)
+        .unwrap();
+    market
+        .create_source_credit_lien_from_insurance_not_atomic(0, BOUND_SCALE)
         .unwrap();
 
     let before_insurance = market.header.insurance;
@@ -1638,7 +2030,17 @@ fn v16_resolved_payout_topup_finishes_receipt_without_overpaying() {
     let mut account_header = account_fixture(1, 13);
     {
         let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
-        market.resolve_market_not_atomic(1).unwrap();
+        market
+            .resolve_market_with_prices_not_atomic(
+                ResolveModeV16::Degenerate,
+                1,
+                &[ResolveAssetPriceV16 {
+                    resolved_price: 100,
+                    live_oracle_price: 100,
+                    funding_rate_e9: 0,
+                }],
+            )
+            .unwrap();
     }
     let terminal_claim = 10u128;
     header.vault = V16PodU128::new(4);
@@ -1745,12 +2147,7 @@ fn v16_risk_increasing_trade_creates_source_credit_lien_for_im() {
         .execute_trade_with_fee_loss_stale_scoped_not_atomic(
             &mut long,
             &mut short,
-            TradeRequestV16 {
-                asset_index: 0,
-                size_q: signed_q(10 * POS_SCALE),
-                exec_price: 1,
-                fee_bps: 0,
-            },
+            TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 1, 0),
         )
         .expect("risk-increasing trade should atomically lien backed source credit for IM");
 
@@ -1830,12 +2227,7 @@ fn v16_residual_reward_credit_uses_real_principal_not_notional() {
         .execute_trade_with_fee_loss_stale_scoped_not_atomic(
             &mut taker,
             &mut lp,
-            TradeRequestV16 {
-                asset_index: 0,
-                size_q: signed_q(POS_SCALE),
-                exec_price: 1_000,
-                fee_bps: 0,
-            },
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 1_000, 0),
         )
         .unwrap();
 
@@ -1878,12 +2270,7 @@ fn v16_residual_reward_credit_is_capped_by_available_crystallized_loss() {
         .execute_trade_with_fee_loss_stale_scoped_not_atomic(
             &mut taker,
             &mut lp,
-            TradeRequestV16 {
-                asset_index: 0,
-                size_q: signed_q(POS_SCALE),
-                exec_price: 1_000,
-                fee_bps: 0,
-            },
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 1_000, 0),
         )
         .unwrap();
 
@@ -2062,12 +2449,7 @@ fn v16_trade_created_parked_source_claim_survives_later_deposit() {
             .execute_trade_with_fee_loss_stale_scoped_not_atomic(
                 &mut long,
                 &mut short,
-                TradeRequestV16 {
-                    asset_index: 0,
-                    size_q: signed_q(POS_SCALE),
-                    exec_price: 100,
-                    fee_bps: 0,
-                },
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
             )
             .unwrap();
         market
@@ -2140,3 +2522,1866 @@ fn v16_grant_source_positive_pnl_attributes_claims_and_aggregates_in_lockstep()
     assert_eq!(err, Err(V16Error::LockActive));
     assert_eq!(account.header.pnl.get(), 25);
 }
+
+fn refresh_crank(now_slot: u64, effective_price: u64) -> PermissionlessCrankRequestV16 {
+    PermissionlessCrankRequestV16 {
+        now_slot,
+        asset_index: 0,
+        effective_price,
+        funding_rate_e9: 0,
+        action: PermissionlessCrankActionV16::Refresh,
+    }
+}
+
+#[test]
+fn v16_admitted_gain_without_residual_cover_is_reserved_for_h_max() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 241);
+    let mut short_header = account_fixture(1, 242);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    let admission = WarmupAdmissionV16 {
+        admit_h_min: 4,
+        admit_h_max: 10,
+    };
+    let requests = [TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0)];
+    market
+        .execute_batch_with_admission_not_atomic(&mut long, &mut short, &requests, admission)
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 2, 110, 0, true)
+        .unwrap();
+    market
+        .permissionless_crank_with_admission_not_atomic(&mut long, refresh_crank(2, 110), admission)
+        .unwrap();
+
+    // No residual backs the fresh gain, so admission falls to h_max.
+    assert_eq!(long.header.pnl.get(), 10);
+    assert_eq!(long.header.reserved_pnl.get(), 10);
+    let reserve = long.header.warmup_reserve.try_to_runtime().unwrap();
+    assert_eq!(reserve.sched_start_slot, 2);
+    assert_eq!(reserve.sched_horizon, 10);
+    assert_eq!(reserve.sched_remaining, 10);
+    assert_eq!(market.header.pnl_matured_pos_tot.get(), 0);
+
+    market
+        .accrue_asset_to_not_atomic(0, 7, 110, 0, true)
+        .unwrap();
+    assert_eq!(market.advance_profit_warmup_not_atomic(&mut long), Ok(5));
+    assert_eq!(long.header.reserved_pnl.get(), 5);
+    assert_eq!(market.header.pnl_matured_pos_tot.get(), 5);
+
+    market
+        .accrue_asset_to_not_atomic(0, 20, 110, 0, true)
+        .unwrap();
+    assert_eq!(market.advance_profit_warmup_not_atomic(&mut long), Ok(5));
+    assert_eq!(long.header.reserved_pnl.get(), 0);
+    assert_eq!(
+        long.header.warmup_reserve.try_to_runtime().unwrap(),
+        WarmupReserveV16::EMPTY
+    );
+    assert_eq!(market.header.pnl_matured_pos_tot.get(), 10);
+    long.validate_with_market(&market.as_view()).unwrap();
+}
+
+#[test]
+fn v16_admission_pair_is_bounded_by_config_and_h_min_zero_releases_covered_gain() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 243);
+    let mut short_header = account_fixture(1, 244);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    let requests = [TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0)];
+    assert_eq!(
+        market.call_with_reason(|m| m.execute_batch_with_admission_not_atomic(
+            &mut long,
+            &mut short,
+            &requests,
+            WarmupAdmissionV16 {
+                admit_h_min: 0,
+                admit_h_max: 11,
+            },
+        )),
+        Err(V16Error::InvalidConfig.with_reason(V16ErrorReason::WarmupAdmissionOutOfBounds))
+    );
+
+    let immediate = WarmupAdmissionV16 {
+        admit_h_min: 0,
+        admit_h_max: 10,
+    };
+    market
+        .execute_batch_with_admission_not_atomic(&mut long, &mut short, &requests, immediate)
+        .unwrap();
+    market.header.vault = V16PodU128::new(market.header.vault.get() + 1_000);
+    market
+        .accrue_asset_to_not_atomic(0, 2, 110, 0, true)
+        .unwrap();
+    market
+        .permissionless_crank_with_admission_not_atomic(&mut long, refresh_crank(2, 110), immediate)
+        .unwrap();
+
+    assert_eq!(long.header.pnl.get(), 10);
+    assert_eq!(long.header.reserved_pnl.get(), 0);
+    assert_eq!(market.header.pnl_matured_pos_tot.get(), 10);
+    long.validate_with_market(&market.as_view()).unwrap();
+}
+
+#[test]
+fn v16_plain_paths_reserve_fresh_gain_under_the_default_admission() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    assert_eq!(
+        (config.default_admit_h_min, config.default_admit_h_max),
+        (0, 10)
+    );
+    config.default_admit_h_max = 0;
+    assert_eq!(
+        config.validate_public_user_fund(),
+        Err(V16Error::InvalidConfig)
+    );
+    config.default_admit_h_max = 11;
+    assert_eq!(
+        config.validate_public_user_fund(),
+        Err(V16Error::InvalidConfig)
+    );
+    // Shortening h_max through governance pulls the default pair in with it.
+    config.default_admit_h_max = 10;
+    let mut shorter = ConfigUpdateV16::from_config(&config);
+    shorter.h_max = 6;
+    let updated = shorter.apply_to(config);
+    assert_eq!(
+        updated.default_warmup_admission(),
+        WarmupAdmissionV16 {
+            admit_h_min: 0,
+            admit_h_max: 6,
+        }
+    );
+    assert_eq!(updated.validate_public_user_fund(), Ok(()));
+
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 245);
+    let mut short_header = account_fixture(1, 246);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 2, 110, 0, true)
+        .unwrap();
+    market
+        .permissionless_crank_not_atomic(&mut long, refresh_crank(2, 110))
+        .unwrap();
+
+    // The default pair is (0, 10); with no residual behind the gain it falls to h_max.
+    assert_eq!(long.header.pnl.get(), 10);
+    assert_eq!(long.header.reserved_pnl.get(), 10);
+    let reserve = long.header.warmup_reserve.try_to_runtime().unwrap();
+    assert_eq!(reserve.sched_horizon, 10);
+    assert_eq!(market.header.pnl_matured_pos_tot.get(), 0);
+}
+
+#[test]
+fn v16_keeper_crank_liquidates_candidates_and_wraps_round_robin_sweep() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.initial_margin_bps = V16PodU64::new(5_000);
+    header.config.maintenance_margin_bps = V16PodU64::new(2_500);
+    let mut idle_header = account_fixture(1, 251);
+    let mut long_header = account_fixture(1, 252);
+    let mut short_header = account_fixture(1, 253);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    {
+        let mut long = PortfolioV16ViewMut::new(&mut long_header);
+        let mut short = PortfolioV16ViewMut::new(&mut short_header);
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(
+                &mut PortfolioV16ViewMut::new(&mut idle_header),
+            )
+            .unwrap();
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(&mut long)
+            .unwrap();
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(&mut short)
+            .unwrap();
+        market.deposit_not_atomic(&mut long, 50).unwrap();
+        market.deposit_not_atomic(&mut short, 1_000).unwrap();
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+            )
+            .unwrap();
+    }
+    let request = |now_slot, rr_window_size| KeeperCrankRequestV16 {
+        now_slot,
+        asset_index: 0,
+        effective_price: 60,
+        funding_rate_e9: 0,
+        admission: WarmupAdmissionV16 {
+            admit_h_min: 4,
+            admit_h_max: 10,
+        },
+        max_revalidations: 2,
+        rr_window_size,
+    };
+    {
+        let mut candidates = [
+            KeeperCandidateV16 {
+                account: PortfolioV16ViewMut::new(&mut long_header),
+                liquidation: Some(LiquidationRequestV16 {
+                    asset_index: 0,
+                    close_q: POS_SCALE,
+                    fee_bps: 0,
+                }),
+            },
+            KeeperCandidateV16 {
+                account: PortfolioV16ViewMut::new(&mut short_header),
+                liquidation: None,
+            },
+        ];
+        let mut sweep = [PortfolioV16ViewMut::new(&mut idle_header)];
+        assert_eq!(
+            market.keeper_crank_not_atomic(request(2, 2), &mut candidates, &mut sweep),
+            Err(V16Error::InvalidConfig)
+        );
+        let outcome = market
+            .keeper_crank_not_atomic(request(2, 1), &mut candidates, &mut sweep)
+            .unwrap();
+        // Closing the only long empties the side, which schedules a reset and ends Phase 1
+        // before the short candidate; the sweep still runs.
+        assert_eq!(
+            outcome,
+            KeeperCrankOutcomeV16 {
+                revalidated: 1,
+                liquidated: 1,
+                liquidations_skipped: 0,
+                stopped_on_pending_reset: true,
+                swept: 1,
+                rr_cursor_position: 1,
+                sweep_generation: 0,
+            }
+        );
+        assert_eq!(candidates[0].account.header.active_bitmap[0].get(), 0);
+    }
+    let mut sweep = [
+        PortfolioV16ViewMut::new(&mut long_header),
+        PortfolioV16ViewMut::new(&mut short_header),
+    ];
+    let outcome = market
+        .keeper_crank_not_atomic(request(3, 5), &mut [], &mut sweep)
+        .unwrap();
+    assert_eq!(outcome.swept, 2);
+    assert_eq!(outcome.rr_cursor_position, 0);
+    assert_eq!(outcome.sweep_generation, 1);
+    assert_eq!(market.header.rr_cursor_position.get(), 0);
+    assert_eq!(market.header.sweep_generation.get(), 1);
+}
+
+#[test]
+fn v16_indexed_deregister_moves_the_last_portfolio_into_the_freed_index() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut first_header = account_fixture(1, 241);
+    let mut second_header = account_fixture(1, 242);
+    let mut third_header = account_fixture(1, 243);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut first = PortfolioV16ViewMut::new(&mut first_header);
+    let mut second = PortfolioV16ViewMut::new(&mut second_header);
+    let mut third = PortfolioV16ViewMut::new(&mut third_header);
+    for account in [&mut first, &mut second, &mut third] {
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(account)
+            .unwrap();
+    }
+    assert_eq!(third.header.materialized_index.get(), 2);
+
+    // A stamped portfolio cannot leave through the unindexed entry point, and freeing index 0
+    // needs the portfolio at the last index to fill it.
+    assert_eq!(
+        market.deregister_empty_materialized_portfolio_not_atomic(&first.as_view()),
+        Err(V16Error::InvalidConfig)
+    );
+    assert_eq!(
+        market.deregister_empty_materialized_portfolio_indexed_not_atomic(&mut first, None),
+        Err(V16Error::InvalidConfig)
+    );
+    assert_eq!(
+        market.deregister_empty_materialized_portfolio_indexed_not_atomic(
+            &mut first,
+            Some(&mut second)
+        ),
+        Err(V16Error::InvalidConfig)
+    );
+    market
+        .deregister_empty_materialized_portfolio_indexed_not_atomic(&mut first, Some(&mut third))
+        .unwrap();
+    assert_eq!(third.header.materialized_index.get(), 0);
+    assert_eq!(market.header.materialized_portfolio_count.get(), 2);
+    market
+        .deregister_empty_materialized_portfolio_indexed_not_atomic(&mut second, None)
+        .unwrap();
+    assert_eq!(market.header.materialized_portfolio_count.get(), 1);
+    for account in [&first, &second, &third] {
+        account.validate_with_market(&market.as_view()).unwrap();
+    }
+}
+
+#[test]
+fn v16_keeper_crank_binds_sweep_to_cursor_and_skips_healthy_liquidations() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.initial_margin_bps = V16PodU64::new(5_000);
+    header.config.maintenance_margin_bps = V16PodU64::new(2_500);
+    let mut idle_header = account_fixture(1, 254);
+    let mut long_header = account_fixture(1, 255);
+    let mut short_header = account_fixture(1, 250);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    {
+        let mut idle = PortfolioV16ViewMut::new(&mut idle_header);
+        let mut long = PortfolioV16ViewMut::new(&mut long_header);
+        let mut short = PortfolioV16ViewMut::new(&mut short_header);
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(&mut idle)
+            .unwrap();
+        market
+            .register_empty_materialized_portfolio_indexed_not_atomic(&mut long)
+            .unwrap();
+        assert_eq!(
+            market.register_empty_materialized_portfolio_indexed_not_atomic(&mut long),
+            Err(V16Error::InvalidConfig)
+        );
+        market.deposit_not_atomic(&mut long, 50).unwrap();
+        market.deposit_not_atomic(&mut short, 1_000).unwrap();
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+            )
+            .unwrap();
+    }
+    let request = KeeperCrankRequestV16 {
+        now_slot: 2,
+        asset_index: 0,
+        effective_price: 60,
+        funding_rate_e9: 0,
+        admission: WarmupAdmissionV16 {
+            admit_h_min: 0,
+            admit_h_max: 10,
+        },
+        max_revalidations: 1,
+        rr_window_size: 1,
+    };
+
+    // The sweep window starts at index 0, which is the idle portfolio, not the long one; the
+    // unregistered short cannot be swept at all.
+    let mut sweep = [PortfolioV16ViewMut::new(&mut long_header)];
+    assert_eq!(
+        market.keeper_crank_not_atomic(request, &mut [], &mut sweep),
+        Err(V16Error::InvalidConfig)
+    );
+    let mut sweep = [PortfolioV16ViewMut::new(&mut short_header)];
+    assert_eq!(
+        market.keeper_crank_not_atomic(request, &mut [], &mut sweep),
+        Err(V16Error::InvalidConfig)
+    );
+    let mut idle_copy = idle_header;
+    let mut candidates = [KeeperCandidateV16 {
+        account: PortfolioV16ViewMut::new(&mut idle_copy),
+        liquidation: None,
+    }];
+    let mut sweep = [PortfolioV16ViewMut::new(&mut idle_header)];
+    assert_eq!(
+        market.keeper_crank_not_atomic(request, &mut candidates, &mut sweep),
+        Err(V16Error::InvalidConfig)
+    );
+
+    // At the entry price the long is healthy, so its requested liquidation is passed over.
+    let liquidation = |fee_bps| {
+        Some(LiquidationRequestV16 {
+            asset_index: 0,
+            close_q: POS_SCALE,
+            fee_bps,
+        })
+    };
+    let mut candidates = [KeeperCandidateV16 {
+        account: PortfolioV16ViewMut::new(&mut long_header),
+        liquidation: liquidation(0),
+    }];
+    let mut sweep = [PortfolioV16ViewMut::new(&mut idle_header)];
+    let healthy = KeeperCrankRequestV16 {
+        now_slot: 1,
+        effective_price: 100,
+        ..request
+    };
+    let outcome = market
+        .keeper_crank_not_atomic(healthy, &mut candidates, &mut sweep)
+        .unwrap();
+    assert_eq!(outcome.revalidated, 1);
+    assert_eq!(outcome.liquidated, 0);
+    assert_eq!(outcome.liquidations_skipped, 1);
+    assert_eq!(outcome.rr_cursor_position, 1);
+
+    // Once underwater, a malformed request aborts the crank instead of being skipped.
+    let request = KeeperCrankRequestV16 {
+        rr_window_size: 0,
+        ..request
+    };
+    candidates[0].liquidation = liquidation(10_001);
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.keeper_crank_not_atomic(request, &mut candidates, &mut []))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::LiquidationRequestOutOfBounds
+    );
+    candidates[0].liquidation = liquidation(0);
+    let outcome = market
+        .keeper_crank_not_atomic(request, &mut candidates, &mut [])
+        .unwrap();
+    assert_eq!(outcome.liquidated, 1);
+    assert_eq!(outcome.liquidations_skipped, 0);
+    candidates[0]
+        .account
+        .validate_with_market(&market.as_view())
+        .unwrap();
+}
+
+#[test]
+fn v16_oracle_catchup_walks_capped_staircase_to_raw_target() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 61);
+    let mut short_header = account_fixture(1, 62);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    market
+        .set_asset_raw_oracle_target_not_atomic(0, 350)
+        .unwrap();
+
+    // The fixture caps moves at 100% per slot and one slot per accrual segment.
+    assert_eq!(market.next_oracle_catchup_price(0, 1), Ok(100));
+    assert_eq!(market.next_oracle_catchup_price(0, 4), Ok(200));
+    let outcome = market
+        .catch_up_asset_oracle_not_atomic(0, 4, 0, 1, true)
+        .unwrap();
+    assert_eq!(
+        outcome,
+        OracleCatchupOutcomeV16 {
+            segments: 1,
+            effective_price: 200,
+            slot_last: 2,
+            caught_up: false,
+        }
+    );
+    let outcome = market
+        .catch_up_asset_oracle_not_atomic(0, 4, 0, 8, true)
+        .unwrap();
+    assert_eq!(
+        outcome,
+        OracleCatchupOutcomeV16 {
+            segments: 2,
+            effective_price: 350,
+            slot_last: 4,
+            caught_up: true,
+        }
+    );
+
+    market
+        .set_asset_raw_oracle_target_not_atomic(0, 351)
+        .unwrap();
+    market.header.config.max_price_move_bps_per_slot = V16PodU64::new(1);
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.next_oracle_catchup_price(0, 5))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::OracleCatchupStalled)
+    );
+}
+
+#[test]
+fn v16_resolve_with_prices_bands_ordinary_and_skips_band_for_degenerate() {
+    let price = |resolved_price, live_oracle_price| ResolveAssetPriceV16 {
+        resolved_price,
+        live_oracle_price,
+        funding_rate_e9: 0,
+    };
+
+    let (mut header, mut markets) = market_fixture(1, 100);
+    assert_eq!(
+        header.config.resolve_price_deviation_bps.get(),
+        DEFAULT_RESOLVE_PRICE_DEVIATION_BPS
+    );
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let err = market
+            .call_with_reason(|m| {
+                m.resolve_market_with_prices_not_atomic(
+                    ResolveModeV16::Ordinary,
+                    3,
+                    &[price(106, 100)],
+                )
+            })
+            .unwrap_err();
+        assert_eq!(err.error, V16Error::InvalidConfig);
+        assert_eq!(err.reason, V16ErrorReason::ResolvePriceOutOfBand);
+    }
+
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.resolve_price_deviation_bps = V16PodU64::new(500);
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        market
+            .resolve_market_with_prices_not_atomic(ResolveModeV16::Ordinary, 3, &[price(105, 100)])
+            .unwrap();
+        let asset = market.markets[0].engine.asset.try_to_runtime().unwrap();
+        assert_eq!(asset.effective_price, 105);
+        assert_eq!(asset.slot_last, 3);
+        assert_eq!(asset.k_long, -asset.k_short);
+        assert_eq!(market.header.resolved_slot.get(), 3);
+        assert_eq!(market.validate_shape(), Ok(()));
+    }
+
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.resolve_price_deviation_bps = V16PodU64::new(500);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.resolve_market_with_prices_not_atomic(
+                ResolveModeV16::Degenerate,
+                3,
+                &[price(150, 99)]
+            ))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::ResolveDegenerateRequiresLastPrice
+    );
+    market
+        .resolve_market_with_prices_not_atomic(ResolveModeV16::Degenerate, 3, &[price(150, 100)])
+        .unwrap();
+    let asset = market.markets[0].engine.asset.try_to_runtime().unwrap();
+    assert_eq!(asset.effective_price, 150);
+    assert_eq!(market.validate_shape(), Ok(()));
+}
+
+#[test]
+fn v16_accrual_resolve_and_margin_rejections_carry_reasons() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut account_header = account_fixture(1, 245);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut account = PortfolioV16ViewMut::new(&mut account_header);
+
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.accrue_asset_to_not_atomic(1, 2, 100, 0, true))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::AccrualRequestOutOfBounds)
+    );
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.accrue_asset_to_not_atomic(0, 2, 100, i128::MAX, true))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::AccrualFundingRateOutOfBounds)
+    );
+    let now_slot = market.header.current_slot.get();
+    assert_eq!(
+        market.call_with_reason(|m| m.set_account_initial_margin_bps_not_atomic(
+            &mut account,
+            u64::MAX,
+            now_slot
+        )),
+        Err(V16Error::InvalidConfig.with_reason(V16ErrorReason::AccountInitialMarginOutOfBounds))
+    );
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.resolve_market_with_prices_not_atomic(
+                ResolveModeV16::Ordinary,
+                3,
+                &[]
+            ))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::ResolvePriceCountMismatch)
+    );
+    assert_eq!(market.header.resolved_slot.get(), 0);
+}
+
+#[test]
+fn v16_single_asset_resolution_settles_legs_while_group_stays_live() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    header.config.resolve_price_deviation_bps = V16PodU64::new(1_000);
+    let mut long_header = account_fixture(2, 71);
+    let mut short_header = account_fixture(2, 72);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    let trade = TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0);
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, trade)
+        .unwrap();
+
+    market
+        .resolve_asset_not_atomic(
+            0,
+            2,
+            ResolveAssetPriceV16 {
+                resolved_price: 110,
+                live_oracle_price: 100,
+                funding_rate_e9: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(market.header.mode, 0); // Live
+    let asset = market.markets[0].engine.asset.try_to_runtime().unwrap();
+    assert_eq!(asset.lifecycle, AssetLifecycleV16::DrainOnly);
+    assert_eq!((asset.resolved_slot, asset.resolved_price), (2, 110));
+    assert_eq!(
+        market
+            .call_with_reason(|m| m
+                .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut short, &mut long, trade))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::AssetResolved
+    );
+    market
+        .accrue_asset_to_not_atomic(1, 3, 100, 0, true)
+        .unwrap();
+
+    let closed = market
+        .close_resolved_asset_leg_not_atomic(&mut long, 0, u128::MAX)
+        .unwrap();
+    assert!(closed.detached);
+    assert_eq!(closed.positive_pnl_forfeited, 0);
+    assert_eq!(long.header.pnl.get(), 10);
+    let closed = market
+        .close_resolved_asset_leg_not_atomic(&mut short, 0, u128::MAX)
+        .unwrap();
+    assert!(closed.detached);
+    assert_eq!(short.header.pnl.get(), 0);
+    assert_eq!(short.header.capital.get(), 990);
+
+    let asset = market.markets[0].engine.asset.try_to_runtime().unwrap();
+    assert_eq!((asset.oi_eff_long_q, asset.oi_eff_short_q), (0, 0));
+    assert_eq!(
+        (asset.stored_pos_count_long, asset.stored_pos_count_short),
+        (0, 0)
+    );
+    assert_eq!(market.validate_shape(), Ok(()));
+}
+
+#[test]
+fn v16_recovery_settles_at_bounded_fallback_price_then_closes_resolved() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.max_recovery_fallback_deviation_bps = V16PodU64::new(500);
+    let mut long_header = account_fixture(1, 81);
+    let mut short_header = account_fixture(1, 82);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.settle_recovery_at_fallback_prices_not_atomic(2, &[105]))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::MarketNotInRecovery
+    );
+    market
+        .permissionless_crank_not_atomic(
+            &mut short,
+            PermissionlessCrankRequestV16 {
+                now_slot: 1,
+                asset_index: 0,
+                effective_price: 100,
+                funding_rate_e9: 0,
+                action: PermissionlessCrankActionV16::Recover(
+                    PermissionlessRecoveryReasonV16::OracleOrTargetUnavailableByAuthenticatedPolicy,
+                ),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.settle_recovery_at_fallback_prices_not_atomic(2, &[106]))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::RecoveryFallbackPriceOutOfBand
+    );
+    market
+        .settle_recovery_at_fallback_prices_not_atomic(2, &[105])
+        .unwrap();
+    assert_eq!(market.header.mode, 1); // Resolved
+    assert_eq!(market.header.resolved_slot.get(), 2);
+    assert_eq!(
+        market.markets[0]
+            .engine
+            .asset
+            .try_to_runtime()
+            .unwrap()
+            .effective_price,
+        105
+    );
+
+    assert_eq!(
+        market.close_resolved_account_not_atomic(&mut short, 0),
+        Ok(ResolvedCloseOutcomeV16::Closed { payout: 995 })
+    );
+}
+
+#[test]
+fn v16_asset_risk_profile_is_envelope_checked_and_drives_leg_margin() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut long_header = account_fixture(2, 91);
+    let mut short_header = account_fixture(2, 92);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 60).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000).unwrap();
+
+    let mut profile = AssetRiskProfileV16 {
+        enabled: true,
+        maintenance_margin_bps: 2_500,
+        initial_margin_bps: 5_000,
+        liquidation_fee_bps: 0,
+        max_price_move_bps_per_slot: 10_000,
+        max_abs_funding_e9_per_slot: 0,
+    };
+    let now = market.header.current_slot.get();
+    let update = asset_risk_profile_update(&market, 1, profile);
+    assert_eq!(
+        market.propose_config_update_not_atomic(update, now),
+        Err(V16Error::InvalidConfig)
+    );
+    profile.max_price_move_bps_per_slot = 1_000;
+    // A lower per-asset price cap tightens margin, so the profile waits out the grace period.
+    let update = asset_risk_profile_update(&market, 1, profile);
+    let config = market.header.config.try_to_runtime_shape().unwrap();
+    assert_eq!(
+        market.propose_config_update_not_atomic(update, now),
+        Ok(now + config.config_update_timelock_slots + config.config_update_grace_slots)
+    );
+    assert_eq!(
+        market.markets[1].engine.risk_profile.try_to_runtime(),
+        Ok(AssetRiskProfileV16::EMPTY)
+    );
+    market.cancel_config_update_not_atomic().unwrap();
+    let risk_epoch_before = market.header.risk_epoch.get();
+    activate_config_update(&mut market, update).unwrap();
+    assert_eq!(market.header.risk_epoch.get(), risk_epoch_before + 1);
+    assert_eq!(
+        market.markets[1].engine.risk_profile.try_to_runtime(),
+        Ok(profile)
+    );
+
+    // 60 of capital covers the profile's 50% IM on a 100 notional, not the group's 100%.
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(1, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    let cert = long.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_initial_req, 50);
+    assert_eq!(cert.certified_maintenance_req, 25);
+}
+
+#[test]
+fn v16_portfolio_margin_offset_is_envelope_bounded_and_credits_opposite_legs() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut spread_header = account_fixture(2, 93);
+    let mut counter_header = account_fixture(2, 94);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut spread = PortfolioV16ViewMut::new(&mut spread_header);
+    let mut counter = PortfolioV16ViewMut::new(&mut counter_header);
+    market.deposit_not_atomic(&mut spread, 60).unwrap();
+    market.deposit_not_atomic(&mut counter, 1_000).unwrap();
+    let profile = AssetRiskProfileV16 {
+        enabled: true,
+        maintenance_margin_bps: 2_500,
+        initial_margin_bps: 5_000,
+        liquidation_fee_bps: 0,
+        max_price_move_bps_per_slot: 1_000,
+        max_abs_funding_e9_per_slot: 0,
+    };
+    let update = asset_risk_profile_update(&market, 0, profile);
+    activate_config_update(&mut market, update).unwrap();
+    let update = asset_risk_profile_update(&market, 1, profile);
+    activate_config_update(&mut market, update).unwrap();
+
+    // A 2_500 bps MM over a 1_000 bps one-slot move leaves 60% of the requirement as slack.
+    let config = market.header.config.try_to_runtime_shape().unwrap();
+    let mut offsets = [PortfolioMarginOffsetV16::EMPTY; V16_MAX_PORTFOLIO_MARGIN_OFFSETS];
+    offsets[0] = PortfolioMarginOffsetV16 {
+        asset_a: 0,
+        asset_b: 1,
+        offset_bps: 6_001,
+    };
+    let update = ConfigUpdateV16 {
+        portfolio_margin_offsets: Some(offsets),
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    let now = market.header.current_slot.get();
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.propose_config_update_not_atomic(update, now))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::PortfolioMarginOffsetUnsafe
+    );
+    offsets[0].offset_bps = 5_000;
+    let update = ConfigUpdateV16 {
+        portfolio_margin_offsets: Some(offsets),
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    activate_config_update(&mut market, update).unwrap();
+
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut spread,
+            &mut counter,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut counter,
+            &mut spread,
+            TradeRequestV16::new(1, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    // Two 50-IM legs on opposite sides: half of each is credited back, so 60 of capital holds
+    // the spread that would need 100 without the offset. Credits round down (12 of 25 MM).
+    let cert = spread.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_initial_req, 50);
+    assert_eq!(cert.certified_maintenance_req, 26);
+
+    // Shrinking a pair's credit can leave the spread under-margined, so it waits out the grace
+    // period; widening it does not.
+    market.header.config.config_update_grace_slots = V16PodU64::new(5);
+    let now = market.header.current_slot.get();
+    offsets[0].offset_bps = 4_000;
+    let update = ConfigUpdateV16 {
+        portfolio_margin_offsets: Some(offsets),
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    assert_eq!(
+        market.propose_config_update_not_atomic(update, now),
+        Ok(now + 6)
+    );
+    market.cancel_config_update_not_atomic().unwrap();
+    offsets[0].offset_bps = 6_000;
+    let update = ConfigUpdateV16 {
+        portfolio_margin_offsets: Some(offsets),
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    assert_eq!(
+        market.propose_config_update_not_atomic(update, now),
+        Ok(now + 1)
+    );
+}
+
+#[test]
+fn v16_isolated_leg_is_margined_and_liquidated_on_its_own_allocation() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut isolated_header = account_fixture(2, 95);
+    let mut counter_header = account_fixture(2, 96);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut isolated = PortfolioV16ViewMut::new(&mut isolated_header);
+    let mut counter = PortfolioV16ViewMut::new(&mut counter_header);
+    market.deposit_not_atomic(&mut isolated, 300).unwrap();
+    market.deposit_not_atomic(&mut counter, 1_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut counter,
+            &mut isolated,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut isolated,
+            &mut counter,
+            TradeRequestV16::new(1, signed_q(POS_SCALE), 100, 0),
+        )
+        .unwrap();
+
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.set_isolated_margin_not_atomic(&mut isolated, 0, 99))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::IsolatedMarginBelowInitial
+    );
+    market
+        .set_isolated_margin_not_atomic(&mut isolated, 0, 100)
+        .unwrap();
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.set_isolated_margin_not_atomic(&mut isolated, 1, 201))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::IsolatedMarginExceedsCapital
+    );
+    let liquidate = |asset_index| LiquidationRequestV16 {
+        asset_index,
+        close_q: POS_SCALE,
+        fee_bps: 0,
+    };
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.liquidate_account_not_atomic(&mut isolated, liquidate(0)))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::LiquidationIsolatedMarginHealthy
+    );
+
+    // The short loses 50 of its 100 allocation against a 150 requirement. The shared pool's
+    // surplus keeps the account as a whole healthy, so only the isolated leg can be closed.
+    market
+        .accrue_asset_to_not_atomic(0, 2, 150, 0, true)
+        .unwrap();
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.liquidate_account_not_atomic(&mut isolated, liquidate(1)))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::LiquidationAccountHealthy
+    );
+    let out = market
+        .liquidate_account_not_atomic(&mut isolated, liquidate(0))
+        .unwrap();
+    assert_eq!(out.closed_q, POS_SCALE);
+    assert_eq!(isolated.header.capital.get(), 250);
+    assert_eq!(
+        isolated.header.isolated_margin[0].try_to_runtime().unwrap(),
+        IsolatedMarginV16::EMPTY
+    );
+    isolated.validate_with_market(&market.as_view()).unwrap();
+}
+
+#[test]
+fn v16_isolated_leg_loss_beyond_allocation_is_socialized_not_charged_to_cross_capital() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    let mut isolated_header = account_fixture(2, 93);
+    let mut counter_header = account_fixture(2, 94);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut isolated = PortfolioV16ViewMut::new(&mut isolated_header);
+    let mut counter = PortfolioV16ViewMut::new(&mut counter_header);
+    market.deposit_not_atomic(&mut isolated, 300).unwrap();
+    market.deposit_not_atomic(&mut counter, 1_000).unwrap();
+    for (asset_index, size_q) in [(0, signed_q(POS_SCALE)), (1, -signed_q(POS_SCALE))] {
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut counter,
+                &mut isolated,
+                TradeRequestV16::new(asset_index, size_q, 100, 0),
+            )
+            .unwrap();
+    }
+    market
+        .set_isolated_margin_not_atomic(&mut isolated, 0, 100)
+        .unwrap();
+    let cross_leg_before = isolated.header.legs[1];
+    let b_long_before = market.markets[0]
+        .engine
+        .asset
+        .try_to_runtime()
+        .unwrap()
+        .b_long_num;
+
+    // The short isolated leg loses 200 against its 100 allocation.
+    market
+        .accrue_asset_to_not_atomic(0, 2, 200, 0, true)
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 3, 300, 0, true)
+        .unwrap();
+    market
+        .full_account_refresh_not_atomic(&mut isolated)
+        .unwrap();
+
+    let leg = isolated.header.isolated_margin[0].try_to_runtime().unwrap();
+    assert_eq!(leg.settled_pnl, -100);
+    assert_eq!(leg.equity(), 0);
+    assert_eq!(
+        isolated.header.capital.get() as i128 + isolated.header.pnl.get(),
+        200
+    );
+    assert_eq!(isolated.header.legs[1], cross_leg_before);
+    let asset = market.markets[0].engine.asset.try_to_runtime().unwrap();
+    assert!(asset.b_long_num > b_long_before);
+    assert_eq!(market.header.bankruptcy_hlock_active, 1);
+    isolated.validate_with_market(&market.as_view()).unwrap();
+}
+
+#[test]
+fn v16_margin_brackets_tier_leg_requirements_by_notional() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.maintenance_margin_bps = 2_500;
+    config.initial_margin_bps = 5_000;
+    config.max_price_move_bps_per_slot = 1_000;
+    config.margin_brackets[0] = MarginBracketV16 {
+        notional_threshold: 1_000,
+        maintenance_margin_bps: 5_000,
+        initial_margin_bps: 7_500,
+    };
+    let mut descending = config;
+    descending.margin_brackets[1] = MarginBracketV16 {
+        notional_threshold: 500,
+        maintenance_margin_bps: 5_000,
+        initial_margin_bps: 7_500,
+    };
+    assert_eq!(
+        descending.validate_public_user_fund(),
+        Err(V16Error::InvalidConfig)
+    );
+    config.validate_public_user_fund().unwrap();
+    // Only the notional above the threshold pays the bracket rate.
+    assert_eq!(config.maintenance_requirement(1_000), Ok(250));
+    assert_eq!(config.maintenance_requirement(2_000), Ok(750));
+    assert_eq!(config.initial_requirement(2_000), Ok(1_250));
+
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 97);
+    let mut short_header = account_fixture(1, 98);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_250).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(20 * POS_SCALE), 100, 0),
+        )
+        .unwrap();
+    let cert = long.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_initial_req, 1_250);
+    assert_eq!(cert.certified_maintenance_req, 750);
+}
+
+/// A 10-unit long opened at 100 on 500 of capital against a well-funded short, with the price
+/// walked down to 66 by slot 5 under 25% maintenance / 50% initial margin. `configure` adjusts
+/// the config before the trade.
+fn underwater_long_fixture(
+    configure: impl FnOnce(&mut V16Config),
+) -> (
+    MarketGroupV16HeaderAccount,
+    Vec<Market<u64>>,
+    PortfolioAccountV16Account,
+    PortfolioAccountV16Account,
+) {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.maintenance_margin_bps = 2_500;
+    config.initial_margin_bps = 5_000;
+    config.max_price_move_bps_per_slot = 1_000;
+    configure(&mut config);
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    {
+        let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+        let mut long = PortfolioV16ViewMut::new(&mut long_header);
+        let mut short = PortfolioV16ViewMut::new(&mut short_header);
+        market.deposit_not_atomic(&mut long, 500).unwrap();
+        market.deposit_not_atomic(&mut short, 10_000).unwrap();
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 0),
+            )
+            .unwrap();
+        for (slot, price) in (2..).zip([90, 81, 73, 66]) {
+            market
+                .accrue_asset_to_not_atomic(0, slot, price, 0, true)
+                .unwrap();
+        }
+    }
+    (header, markets, long_header, short_header)
+}
+
+#[test]
+fn v16_liquidation_close_q_to_restore_sizes_minimal_restoring_close() {
+    let (mut header, mut markets, mut long_header, _) = underwater_long_fixture(|_| {});
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+
+    let liquidate = |close_q| LiquidationRequestV16 {
+        asset_index: 0,
+        close_q,
+        fee_bps: 0,
+    };
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.liquidate_account_not_atomic(&mut long, liquidate(0)))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::LiquidationRequestOutOfBounds
+    );
+
+    // Equity 160 against a 165 requirement: keeping at most 643 of notional at 66 restores it.
+    market.full_account_refresh_not_atomic(&mut long).unwrap();
+    let close_q = market
+        .liquidation_close_q_to_restore(&long.as_view(), 0, 0)
+        .unwrap();
+    let out = market
+        .liquidate_account_not_atomic(&mut long, liquidate(close_q))
+        .unwrap();
+    assert_eq!(out.closed_q, 10 * POS_SCALE - 643 * POS_SCALE / 66);
+    let cert = long.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_liq_deficit, 0);
+    assert_eq!(cert.certified_maintenance_req, 160);
+}
+
+/// Liquidates the refreshed long of a fresh `underwater_long_fixture` by `close_q` of its
+/// restoring size, returning that size and the fee charged with the deficit left behind.
+fn liquidate_underwater_long(
+    configure: impl FnOnce(&mut V16Config),
+    fee_bps: u64,
+    close_q: impl FnOnce(u128) -> u128,
+) -> (u128, Result<(u128, u128), V16ErrorReason>) {
+    let (mut header, mut markets, mut long_header, _) = underwater_long_fixture(configure);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    market.full_account_refresh_not_atomic(&mut long).unwrap();
+    let restoring_q = market
+        .liquidation_close_q_to_restore(&long.as_view(), 0, fee_bps)
+        .unwrap();
+    let request = LiquidationRequestV16 {
+        asset_index: 0,
+        close_q: close_q(restoring_q),
+        fee_bps,
+    };
+    let outcome = market
+        .call_with_reason(|m| m.liquidate_account_not_atomic(&mut long, request))
+        .map(|out| {
+            let cert = long.header.health_cert.try_to_runtime().unwrap();
+            (out.fee_charged, cert.certified_liq_deficit)
+        })
+        .map_err(|rejection| rejection.reason);
+    (restoring_q, outcome)
+}
+
+#[test]
+fn v16_liquidation_close_q_to_restore_is_minimal_under_bound_fees() {
+    // A plain 1% fee, a 0.1% rate floored at 40, and a 20% rate capped at 2.
+    for (fee_bps, min_abs, cap, expected_fee) in [
+        (100, 0, 1_000, None),
+        (10, 40, 1_000, Some(40)),
+        (2_000, 0, 2, Some(2)),
+    ] {
+        let configure = |config: &mut V16Config| {
+            config.liquidation_fee_bps = fee_bps;
+            config.min_liquidation_abs = min_abs;
+            config.liquidation_fee_cap = cap;
+        };
+        let (restoring_q, restored) = liquidate_underwater_long(configure, fee_bps, |q| q);
+        // Paying the fee takes a larger close than the fee-free 10 - 643/66.
+        assert!(restoring_q > 10 * POS_SCALE - 643 * POS_SCALE / 66);
+        assert!(restoring_q < 10 * POS_SCALE);
+        let (fee_charged, deficit) = restored.unwrap();
+        assert_eq!(deficit, 0);
+        assert!(fee_charged > 0);
+        if let Some(expected_fee) = expected_fee {
+            assert_eq!(fee_charged, expected_fee);
+        }
+        let (_, short_of_restoring) = liquidate_underwater_long(configure, fee_bps, |q| q - 1);
+        assert!(!matches!(short_of_restoring, Ok((_, 0))));
+    }
+}
+
+#[test]
+fn v16_permissionless_crank_liquidates_the_minimal_restoring_close() {
+    let (mut header, mut markets, mut long_header, _) = underwater_long_fixture(|config| {
+        config.liquidation_fee_bps = 100;
+        config.liquidation_fee_cap = 1_000;
+    });
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    market.full_account_refresh_not_atomic(&mut long).unwrap();
+    let restoring_q = market
+        .liquidation_close_q_to_restore(&long.as_view(), 0, 100)
+        .unwrap();
+
+    // The requested quantity only caps the close; the crank never takes more than restores.
+    market
+        .permissionless_crank_not_atomic(
+            &mut long,
+            PermissionlessCrankRequestV16 {
+                now_slot: 5,
+                asset_index: 0,
+                effective_price: 66,
+                funding_rate_e9: 0,
+                action: PermissionlessCrankActionV16::Liquidate(LiquidationRequestV16 {
+                    asset_index: 0,
+                    close_q: 10 * POS_SCALE,
+                    fee_bps: 100,
+                }),
+            },
+        )
+        .unwrap();
+    let leg = long.header.legs[0].try_to_runtime().unwrap();
+    assert_eq!(leg.basis_pos_q, signed_q(10 * POS_SCALE - restoring_q));
+    let cert = long.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_liq_deficit, 0);
+}
+
+#[test]
+fn v16_backstop_liquidator_takes_over_leg_at_discount() {
+    let (mut header, mut markets, mut long_header, _) = underwater_long_fixture(|config| {
+        config.liquidation_fee_bps = 100;
+        config.liquidation_fee_cap = 1_000;
+        config.backstop_liquidation_discount_bps = 100;
+    });
+    let mut backstop_header = account_fixture(1, 101);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut backstop = PortfolioV16ViewMut::new(&mut backstop_header);
+    market.deposit_not_atomic(&mut backstop, 10_000).unwrap();
+    market
+        .set_asset_raw_oracle_target_not_atomic(0, 66)
+        .unwrap();
+
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.liquidate_by_transfer_not_atomic(
+                &mut long,
+                &mut backstop,
+                0,
+                0
+            ))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::LiquidationRequestOutOfBounds
+    );
+    // The whole leg moves to the backstop; it is paid ceil(1% of 660) out of the 160 left.
+    let out = market
+        .liquidate_by_transfer_not_atomic(&mut long, &mut backstop, 0, 10 * POS_SCALE)
+        .unwrap();
+    assert_eq!(out.closed_q, 10 * POS_SCALE);
+    assert_eq!(out.fee_charged, 7);
+    assert_eq!(out.residual_booked, 0);
+    assert_eq!(long.header.capital.get(), 153);
+    assert_eq!(long.header.active_bitmap[0].get(), 0);
+    assert_eq!(backstop.header.capital.get(), 10_007);
+    let leg = backstop.header.legs[0].try_to_runtime().unwrap();
+    assert_eq!(
+        (leg.side, leg.basis_pos_q),
+        (SideV16::Long, signed_q(10 * POS_SCALE))
+    );
+    let cert = backstop.header.health_cert.try_to_runtime().unwrap();
+    assert_eq!(cert.certified_initial_req, 330);
+}
+
+#[test]
+fn v16_liquidation_fee_ramps_from_first_unhealthy_slot() {
+    let (mut header, mut markets, mut long_header, _) = underwater_long_fixture(|config| {
+        config.liquidation_fee_bps = 200;
+        config.liquidation_fee_cap = 1_000;
+        config.liquidation_auction_start_bps = 5_000;
+        config.liquidation_auction_ramp_slots = 4;
+        config.max_trading_fee_bps = 300;
+    });
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    market
+        .set_asset_raw_oracle_target_not_atomic(0, 66)
+        .unwrap();
+    market.full_account_refresh_not_atomic(&mut long).unwrap();
+    assert_eq!(long.header.liquidatable_since_slot.get(), 5);
+    assert_eq!(
+        market
+            .liquidation_auction_fee_bps(&long.as_view(), 200)
+            .unwrap(),
+        100
+    );
+
+    // Ramped or not, a request is bounded by the larger of the liquidation and trading fee caps.
+    let request = LiquidationRequestV16 {
+        asset_index: 0,
+        close_q: 10 * POS_SCALE,
+        fee_bps: 301,
+    };
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.liquidate_account_not_atomic(&mut long, request))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::LiquidationRequestOutOfBounds)
+    );
+
+    // Two of four ramp slots later the rate is 150 bps: ceil(660 * 1.5%) = 10.
+    market
+        .accrue_asset_to_not_atomic(0, 7, 66, 0, true)
+        .unwrap();
+    let out = market
+        .liquidate_account_not_atomic(
+            &mut long,
+            LiquidationRequestV16 {
+                fee_bps: 200,
+                ..request
+            },
+        )
+        .unwrap();
+    assert_eq!(out.fee_charged, 10);
+    assert_eq!(long.header.liquidatable_since_slot.get(), 0);
+}
+
+#[test]
+fn v16_account_initial_margin_tightens_at_once_and_loosens_after_timelock() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.maintenance_margin_bps = 1_000;
+    config.initial_margin_bps = 2_000;
+    config.max_price_move_bps_per_slot = 1_000;
+    config.config_update_timelock_slots = 10;
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    let open = TradeRequestV16::new(0, signed_q(25 * POS_SCALE), 100, 0);
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, open)
+        .unwrap();
+
+    // Capping leverage at 2x puts the initial requirement at half of the 2_500 notional.
+    assert_eq!(
+        market
+            .set_account_initial_margin_bps_not_atomic(&mut long, 5_000, 1)
+            .unwrap(),
+        1
+    );
+    let cert = market.full_account_refresh_not_atomic(&mut long).unwrap();
+    assert_eq!(cert.certified_initial_req, 1_250);
+
+    assert_eq!(
+        market
+            .set_account_initial_margin_bps_not_atomic(&mut long, 2_000, 1)
+            .unwrap(),
+        11
+    );
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.activate_account_initial_margin_bps_not_atomic(&mut long, 10))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::ConfigUpdateTimelocked)
+    );
+    assert_eq!(long.header.account_initial_margin_bps.get(), 5_000);
+    market
+        .activate_account_initial_margin_bps_not_atomic(&mut long, 11)
+        .unwrap();
+    let cert = market.full_account_refresh_not_atomic(&mut long).unwrap();
+    assert_eq!(cert.certified_initial_req, 500);
+
+    market
+        .set_account_initial_margin_bps_not_atomic(&mut long, 5_000, 11)
+        .unwrap();
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                TradeRequestV16 {
+                    size_q: signed_q(POS_SCALE),
+                    ..open
+                },
+            ))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::InitialMarginInsufficient)
+    );
+
+    // An isolated leg is held to its own allocation, so the account rate no longer covers it.
+    market
+        .set_isolated_margin_not_atomic(&mut long, 0, 600)
+        .unwrap();
+    let cert = market.full_account_refresh_not_atomic(&mut long).unwrap();
+    assert_eq!(cert.certified_initial_req, 600);
+}
+
+#[test]
+fn v16_config_update_waits_timelock_and_grace_before_activation() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    header.config.config_update_timelock_slots = V16PodU64::new(10);
+    header.config.config_update_grace_slots = V16PodU64::new(5);
+    let config = header.config.try_to_runtime_shape().unwrap();
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let update = ConfigUpdateV16 {
+        maintenance_margin_bps: 5_000,
+        initial_margin_bps: 5_000,
+        h_max: 20,
+        max_price_move_bps_per_slot: 1_000,
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    let invalid = ConfigUpdateV16 {
+        max_price_move_bps_per_slot: 10_000,
+        ..update
+    };
+    assert_eq!(
+        market.propose_config_update_not_atomic(invalid, 1),
+        Err(V16Error::InvalidConfig)
+    );
+    // A longer h_max and a lower price cap tighten, so the grace period applies.
+    assert_eq!(market.propose_config_update_not_atomic(update, 1), Ok(16));
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.propose_config_update_not_atomic(update, 1))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::ConfigUpdateAlreadyPending
+    );
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.activate_config_update_not_atomic(15))
+            .unwrap_err()
+            .reason,
+        V16ErrorReason::ConfigUpdateTimelocked
+    );
+    let risk_epoch_before = market.header.risk_epoch.get();
+    market.activate_config_update_not_atomic(16).unwrap();
+    assert_eq!(market.header.risk_epoch.get(), risk_epoch_before + 1);
+    assert_eq!(market.header.config.maintenance_margin_bps.get(), 5_000);
+    assert_eq!(market.header.config.h_max.get(), 20);
+    assert_eq!(
+        market.header.pending_config_update.try_to_runtime(),
+        Ok(None)
+    );
+
+    // Lowering the base rates activates after the bare timelock.
+    let config = market.header.config.try_to_runtime_shape().unwrap();
+    let looser = ConfigUpdateV16 {
+        maintenance_margin_bps: 4_000,
+        initial_margin_bps: 4_000,
+        ..ConfigUpdateV16::from_config(&config)
+    };
+    assert_eq!(market.propose_config_update_not_atomic(looser, 16), Ok(26));
+    market.cancel_config_update_not_atomic().unwrap();
+    assert_eq!(
+        market.header.pending_config_update.try_to_runtime(),
+        Ok(None)
+    );
+
+    // Adding a bracket above the base rates raises the requirement on large legs.
+    let mut bracketed = ConfigUpdateV16::from_config(&config);
+    bracketed.margin_brackets[0] = MarginBracketV16 {
+        notional_threshold: 1_000,
+        maintenance_margin_bps: 6_000,
+        initial_margin_bps: 6_000,
+    };
+    assert!(bracketed.tightens_margin(&config));
+    assert_eq!(
+        market.propose_config_update_not_atomic(bracketed, 16),
+        Ok(31)
+    );
+    market.cancel_config_update_not_atomic().unwrap();
+
+    let mut unbounded_grace = config;
+    unbounded_grace.config_update_grace_slots = MAX_CONFIG_UPDATE_GRACE_SLOTS + 1;
+    assert_eq!(
+        unbounded_grace.validate_public_user_fund(),
+        Err(V16Error::InvalidConfig)
+    );
+}
+
+#[test]
+fn v16_engine_funding_rate_follows_clamped_premium_over_interest() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.max_abs_funding_e9_per_slot = 10_000;
+    config.funding_twap_window_slots = 1_000;
+    config.funding_interest_rate_e9_per_slot = 100;
+    config.funding_premium_clamp_e9_per_slot = 50;
+    let (mut header, mut markets) = market_fixture(1, 100_000);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000_000).unwrap();
+
+    // No history yet: zero premium, so the clamp caps the interest rate.
+    assert_eq!(market.engine_funding_rate_e9(0), Ok(50));
+
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 100_100, 0),
+        )
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 2, 100_000, 0, true)
+        .unwrap();
+
+    // Mark 0.1% over index across the 1_000-slot window is a 1_000 e9/slot premium; the
+    // interest pull toward 100 is clamped to 50.
+    assert_eq!(market.engine_funding_rate_e9(0), Ok(950));
+    let outcome = market
+        .accrue_asset_to_not_atomic(0, 3, 100_000, 0, true)
+        .unwrap();
+    assert!(outcome.funding_active);
+}
+
+#[test]
+fn v16_engine_funding_mark_is_banded_and_expires_after_the_window() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.max_accrual_dt_slots = 1_000;
+    config.min_funding_lifetime_slots = 1_000;
+    config.max_abs_funding_e9_per_slot = 10_000;
+    config.funding_twap_window_slots = 1_000;
+    config.funding_interest_rate_e9_per_slot = 100;
+    config.funding_premium_clamp_e9_per_slot = 50;
+    config.funding_mark_band_bps = 50;
+    let (mut header, mut markets) = market_fixture(1, 100_000);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 1_000_000).unwrap();
+    market.deposit_not_atomic(&mut short, 1_000_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(POS_SCALE), 110_000, 0),
+        )
+        .unwrap();
+
+    // The model supplies the rate, so a caller-supplied one is refused.
+    assert_eq!(
+        market.accrue_asset_to_not_atomic(0, 2, 100_000, 10, true),
+        Err(V16Error::InvalidConfig)
+    );
+    market
+        .accrue_asset_to_not_atomic(0, 2, 100_000, 0, true)
+        .unwrap();
+    // The 10% fill premium is sampled at the 0.5% band edge, a 5_000 e9/slot premium pulled
+    // toward the interest rate by the clamp; unbanded it would pin the rate at 10_000.
+    assert_eq!(market.engine_funding_rate_e9(0), Ok(4_950));
+
+    market
+        .accrue_asset_to_not_atomic(0, 1_002, 100_000, 0, true)
+        .unwrap();
+    assert_eq!(market.engine_funding_rate_e9(0), Ok(4_950));
+    // A window later the stale fill no longer counts and the window holds only the index.
+    market
+        .accrue_asset_to_not_atomic(0, 2_002, 100_000, 0, true)
+        .unwrap();
+    assert_eq!(market.engine_funding_rate_e9(0), Ok(50));
+}
+
+#[test]
+fn v16_funding_spread_charges_paying_side_and_sweeps_to_insurance() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.max_accrual_dt_slots = 10;
+    config.min_funding_lifetime_slots = 10;
+    config.max_abs_funding_e9_per_slot = 1_000;
+    config.funding_spread_e9_per_slot = 500;
+    let (mut header, mut markets) = market_fixture(1, 1_000_000);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 20_000_000).unwrap();
+    market.deposit_not_atomic(&mut short, 20_000_000).unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 1_000_000, 0),
+        )
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 11, 1_000_000, 1_000, true)
+        .unwrap();
+
+    // Nothing has been collected from the longs yet, so nothing can be swept.
+    assert_eq!(
+        market.sweep_funding_spread_to_insurance_not_atomic(0),
+        Ok(0)
+    );
+    let insurance_before = market.header.insurance.get();
+    let long_capital = long.header.capital.get();
+    let short_capital = short.header.capital.get();
+    market.full_account_refresh_not_atomic(&mut long).unwrap();
+    market.full_account_refresh_not_atomic(&mut short).unwrap();
+
+    // Longs pay 1_500 e9/slot over 10 slots on 10 units at 1_000_000; shorts receive 1_000.
+    assert_eq!(long_capital - long.header.capital.get(), 150);
+    assert_eq!(short.header.pnl.get(), 100);
+    assert_eq!(short.header.capital.get(), short_capital);
+
+    // The whole loss first backs the shorts' claim; once that backing lapses the 50 left above
+    // the claim is surplus the spread sweep can take.
+    assert_eq!(
+        market.sweep_funding_spread_to_insurance_not_atomic(0),
+        Ok(0)
+    );
+    market
+        .expire_source_backing_bucket_not_atomic(0, 21)
+        .unwrap();
+    assert_eq!(
+        market.sweep_funding_spread_to_insurance_not_atomic(0),
+        Ok(50)
+    );
+    assert_eq!(market.header.insurance.get(), insurance_before + 50);
+    assert_eq!(
+        market.sweep_funding_spread_to_insurance_not_atomic(0),
+        Ok(0)
+    );
+}
+
+#[test]
+fn v16_funding_spread_is_forfeited_when_the_paying_loss_is_socialized() {
+    let (mut header, mut markets) = market_fixture(2, 1_000_000);
+    let mut config = header.config.try_to_runtime_shape().unwrap();
+    config.max_accrual_dt_slots = 10;
+    config.min_funding_lifetime_slots = 10;
+    config.max_abs_funding_e9_per_slot = 1_000;
+    config.funding_spread_e9_per_slot = 5_000;
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut isolated_header = account_fixture(2, 93);
+    let mut counter_header = account_fixture(2, 94);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut isolated = PortfolioV16ViewMut::new(&mut isolated_header);
+    let mut counter = PortfolioV16ViewMut::new(&mut counter_header);
+    market.deposit_not_atomic(&mut isolated, 3_000_000).unwrap();
+    market.deposit_not_atomic(&mut counter, 10_000_000).unwrap();
+    for (asset_index, size_q) in [(0, signed_q(POS_SCALE)), (1, -signed_q(POS_SCALE))] {
+        market
+            .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+                &mut counter,
+                &mut isolated,
+                TradeRequestV16::new(asset_index, size_q, 1_000_000, 0),
+            )
+            .unwrap();
+    }
+    market
+        .set_isolated_margin_not_atomic(&mut isolated, 0, 1_000_000)
+        .unwrap();
+
+    // The isolated short pays 1_000 e9/slot plus the 5_000 spread over 10 slots.
+    market
+        .accrue_asset_to_not_atomic(0, 11, 1_000_000, -1_000, true)
+        .unwrap();
+    let spread = |market: &MarketGroupV16ViewMut<'_, u64>| {
+        market.markets[0]
+            .engine
+            .asset
+            .try_to_runtime()
+            .unwrap()
+            .funding_spread_unswept
+    };
+    assert_eq!(spread(&market), 50);
+
+    // The short then loses twice its allocation; the excess is socialized and the spread it
+    // owed is written off instead of being left for a sweep.
+    market
+        .accrue_asset_to_not_atomic(0, 12, 2_000_000, 0, true)
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 13, 3_000_000, 0, true)
+        .unwrap();
+    market
+        .full_account_refresh_not_atomic(&mut isolated)
+        .unwrap();
+    assert_eq!(market.header.bankruptcy_hlock_active, 1);
+    assert_eq!(spread(&market), 0);
+    assert_eq!(
+        market.sweep_funding_spread_to_insurance_not_atomic(0),
+        Ok(0)
+    );
+}
+
+#[test]
+fn v16_price_moving_accrual_feeds_stress_envelope() {
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 10_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+
+    // Unexposed moves consume nothing.
+    market
+        .accrue_asset_to_not_atomic(0, 2, 120, 0, true)
+        .unwrap();
+    assert_eq!(
+        market.header.stress_consumption_bps_e9_since_envelope.get(),
+        0
+    );
+
+    market
+        .set_asset_raw_oracle_target_not_atomic(0, 120)
+        .unwrap();
+    market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 120, 0),
+        )
+        .unwrap();
+    market
+        .accrue_asset_to_not_atomic(0, 3, 132, 0, true)
+        .unwrap();
+    assert_eq!(
+        market.header.stress_consumption_bps_e9_since_envelope.get(),
+        1_000 * STRESS_CONSUMPTION_SCALE
+    );
+    market
+        .accrue_asset_to_not_atomic(0, 4, 99, 0, true)
+        .unwrap();
+    assert_eq!(
+        market.header.stress_consumption_bps_e9_since_envelope.get(),
+        3_500 * STRESS_CONSUMPTION_SCALE
+    );
+    assert_eq!(market.header.threshold_stress_active, 0);
+}
+
+#[test]
+fn v16_batch_maker_rebate_is_paid_from_the_same_fill_taker_fee() {
+    let (mut header, mut markets) = market_fixture(2, 100);
+    header.config.max_trading_fee_bps = V16PodU64::new(100);
+    let mut long_header = account_fixture(2, 99);
+    let mut short_header = account_fixture(2, 100);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 10_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    let fill = TradeRequestV16 {
+        maker_fee_bps: -20,
+        maker: TradeMakerV16::B,
+        ..TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 50)
+    };
+
+    // A rebate rate above the taker rate could pay out more than the fill collects.
+    assert_eq!(
+        market
+            .call_with_reason(|m| m.execute_batch_with_fee_loss_stale_scoped_not_atomic(
+                &mut long,
+                &mut short,
+                &[TradeRequestV16 {
+                    maker_fee_bps: -60,
+                    ..fill
+                }],
+            ))
+            .map_err(|rejection| rejection.reason),
+        Err(V16ErrorReason::TradeRequestOutOfBounds)
+    );
+
+    let insurance_before = market.header.insurance.get();
+    let outcome = market
+        .execute_batch_with_fee_loss_stale_scoped_not_atomic(
+            &mut long,
+            &mut short,
+            &[
+                fill,
+                TradeRequestV16 {
+                    asset_index: 1,
+                    ..fill
+                },
+            ],
+        )
+        .unwrap();
+    // Each 1_000-notional fill charges the taker 5 and rebates the maker 2 of it.
+    assert_eq!(outcome.fee_a, 10);
+    assert_eq!(outcome.fee_b, 0);
+    assert_eq!(outcome.rebate_a, 0);
+    assert_eq!(outcome.rebate_b, 4);
+    assert_eq!(long.header.capital.get(), 9_990);
+    assert_eq!(short.header.capital.get(), 10_004);
+    assert_eq!(market.header.insurance.get(), insurance_before + 6);
+}
+
+#[test]
+fn v16_fee_tier_discount_follows_decayed_traded_notional() {
+    let mut config = V16Config::public_user_fund_with_market_slots(1, 1, 0, 10);
+    config.max_trading_fee_bps = 100;
+    config.max_accrual_dt_slots = 100;
+    config.min_funding_lifetime_slots = 100;
+    config.fee_tiers[0] = FeeTierV16 {
+        volume_threshold: 1_000,
+        fee_discount_bps: 5_000,
+    };
+    config.fee_volume_window_slots = 100;
+    let (mut header, mut markets) = market_fixture(1, 100);
+    let mut long_header = account_fixture(1, 99);
+    let mut short_header = account_fixture(1, 100);
+    header.config = V16ConfigAccount::from_runtime(&config);
+    let mut market = MarketGroupV16ViewMut::new(&mut header, &mut markets);
+    let mut long = PortfolioV16ViewMut::new(&mut long_header);
+    let mut short = PortfolioV16ViewMut::new(&mut short_header);
+    market.deposit_not_atomic(&mut long, 10_000).unwrap();
+    market.deposit_not_atomic(&mut short, 10_000).unwrap();
+    let fill = TradeRequestV16::new(0, signed_q(10 * POS_SCALE), 100, 50);
+
+    // The first 1_000-notional fill pays the full 5; it lifts both accounts into the tier.
+    let outcome = market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, fill)
+        .unwrap();
+    assert_eq!((outcome.fee_a, outcome.fee_b), (5, 5));
+    assert_eq!(long.header.traded_notional_volume.get(), 1_000);
+    let outcome = market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, fill)
+        .unwrap();
+    assert_eq!((outcome.fee_a, outcome.fee_b), (2, 2));
+    assert_eq!(long.header.traded_notional_volume.get(), 2_000);
+
+    // Sixty slots later 40% of the 2_000 remains, below the tier.
+    market
+        .accrue_asset_to_not_atomic(0, 61, 100, 0, true)
+        .unwrap();
+    let outcome = market
+        .execute_trade_with_fee_loss_stale_scoped_not_atomic(&mut long, &mut short, fill)
+        .unwrap();
+    assert_eq!((outcome.fee_a, outcome.fee_b), (5, 5));
+    assert_eq!(long.header.traded_notional_volume.get(), 1_800);
+    assert_eq!(long.header.traded_notional_volume_slot.get(), 61);
+}